@@ -0,0 +1,564 @@
+// guest/src/alarms.rs
+// process limit alarms raised inside the sandbox.
+// a tag may carry hihi / hi / lo / lolo limits; each configured limit is its
+// own alarm, raised when the scaled value passes it and cleared only once the
+// value comes back past the limit by the hysteresis, so a value hovering at
+// the limit doesn't chatter. every sample is evaluated, including ones
+// report-by-exception leaves out of telemetry.
+// each alarm follows the isa-18.2 lifecycle: a raise must be acknowledged,
+// and an alarm that clears unacknowledged stays "returned unacknowledged"
+// until it is. operators may shelve an alarm for a while or suppress it
+// outright; it is still tracked, but its raises and clears are not announced.
+
+use std::collections::HashMap;
+
+use crate::mqtt::payload::{Alarm, AlarmState, Register};
+use crate::register_map::{PointKey, RegisterMap, Table, Tag};
+
+/// which limit an alarm is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Level {
+    LoLo,
+    Lo,
+    Hi,
+    HiHi,
+}
+
+impl Level {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::LoLo => "lolo",
+            Self::Lo => "lo",
+            Self::Hi => "hi",
+            Self::HiHi => "hihi",
+        }
+    }
+
+    /// the outer limits are the urgent ones
+    pub fn severity(self) -> &'static str {
+        match self {
+            Self::LoLo | Self::HiHi => "critical",
+            Self::Lo | Self::Hi => "warning",
+        }
+    }
+
+    fn is_high(self) -> bool {
+        matches!(self, Self::Hi | Self::HiHi)
+    }
+}
+
+/// alarm limits for one tag, in engineering units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub hihi: Option<f64>,
+    pub hi: Option<f64>,
+    pub lo: Option<f64>,
+    pub lolo: Option<f64>,
+    pub hysteresis: f64,  // how far back past a limit the value must come to clear
+}
+
+impl Limits {
+    /// the limit for `level`, if configured
+    pub fn limit(&self, level: Level) -> Option<f64> {
+        match level {
+            Level::LoLo => self.lolo,
+            Level::Lo => self.lo,
+            Level::Hi => self.hi,
+            Level::HiHi => self.hihi,
+        }
+    }
+
+    /// configured limits, lowest first
+    fn thresholds(&self) -> impl Iterator<Item = (Level, f64)> {
+        [
+            (Level::LoLo, self.lolo),
+            (Level::Lo, self.lo),
+            (Level::Hi, self.hi),
+            (Level::HiHi, self.hihi),
+        ]
+        .into_iter()
+        .filter_map(|(level, limit)| Some((level, limit?)))
+    }
+
+    /// reject non-finite values, a negative hysteresis and limits out of
+    /// order - lolo <= lo <= hi <= hihi
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.hysteresis.is_finite() || self.hysteresis < 0.0 {
+            return Err("alarm hysteresis must be finite and non-negative".to_string());
+        }
+        let mut previous: Option<(Level, f64)> = None;
+        for (level, limit) in self.thresholds() {
+            if !limit.is_finite() {
+                return Err(format!("{} limit must be finite", level.name()));
+            }
+            if let Some((lower, below)) = previous {
+                if limit < below {
+                    return Err(format!("{} limit {} is below {} limit {}", level.name(), limit, lower.name(), below));
+                }
+            }
+            previous = Some((level, limit));
+        }
+        if previous.is_none() {
+            return Err("alarm limits set none of hihi, hi, lo or lolo".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// whether an alarm was raised or cleared
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    Raised,
+    Cleared,
+}
+
+impl Transition {
+    pub fn event(self) -> &'static str {
+        match self {
+            Self::Raised => "alarm_raised",
+            Self::Cleared => "alarm_cleared",
+        }
+    }
+}
+
+/// operator command on one alarm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Acknowledge,
+    Shelve(u32),  // hide from operators for this many milliseconds
+    Unshelve,
+    Suppress,     // out of service until unsuppressed
+    Unsuppress,
+}
+
+/// what the tracker asks to be published
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlarmOutput {
+    Event(Transition, Alarm),  // raise or clear, on the alarms topic
+    State(AlarmState),         // retained current state of one alarm
+}
+
+/// identifies one limit of one tag on one slave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct AlarmKey {
+    point: PointKey,
+    level: Level,
+}
+
+/// isa-18.2 state of one alarm
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AlarmRecord {
+    active: bool,                   // the value is past the limit
+    acknowledged: bool,
+    shelved_until_ms: Option<u64>,
+    suppressed: bool,
+    value: Option<f64>,             // value at the last raise or clear
+}
+
+impl Default for AlarmRecord {
+    /// an alarm that has never been raised or commanded
+    fn default() -> Self {
+        Self {
+            active: false,
+            acknowledged: true,
+            shelved_until_ms: None,
+            suppressed: false,
+            value: None,
+        }
+    }
+}
+
+impl AlarmRecord {
+    /// suppression outranks shelving, which outranks the alarm's own state
+    fn state(&self) -> &'static str {
+        if self.suppressed {
+            return "suppressed";
+        }
+        if self.shelved_until_ms.is_some() {
+            return "shelved";
+        }
+        match (self.active, self.acknowledged) {
+            (false, true) => "normal",
+            (true, false) => "unacknowledged",
+            (true, true) => "acknowledged",
+            (false, false) => "returned_unacknowledged",
+        }
+    }
+
+    /// raises and clears only reach operators while the alarm is in service
+    fn annunciated(&self) -> bool {
+        !self.suppressed && self.shelved_until_ms.is_none()
+    }
+
+    fn is_normal(&self) -> bool {
+        self.state() == "normal"
+    }
+}
+
+/// every alarm not in its normal state.
+/// bounded by the register map - at most four per tag with limits.
+#[derive(Debug, Default)]
+pub struct AlarmTracker {
+    alarms: HashMap<AlarmKey, AlarmRecord>,
+}
+
+impl AlarmTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// check tagged registers against their limits. every raise or clear
+    /// changes the alarm's state; only in-service alarms also announce it.
+    /// each new raise needs acknowledging again. nan neither raises nor clears.
+    pub fn evaluate(
+        &mut self,
+        map: &RegisterMap,
+        unit_id: u8,
+        table: Table,
+        registers: &[Register],
+    ) -> Vec<AlarmOutput> {
+        let mut outputs = Vec::new();
+        // unlabelled registers have no tag, or no real address
+        for register in registers.iter().filter(|r| r.label.is_some()) {
+            let point = PointKey { unit_id, table, address: register.address };
+            let Some(tag) = map.get(&point) else { continue };
+            let (Some(limits), Some(value)) = (tag.limits, register.value.as_f64()) else {
+                continue;
+            };
+
+            for (level, limit) in limits.thresholds() {
+                let key = AlarmKey { point, level };
+                let mut record = self.alarms.get(&key).copied().unwrap_or_default();
+                let transition = if level.is_high() {
+                    match record.active {
+                        false if value > limit => Transition::Raised,
+                        true if value <= limit - limits.hysteresis => Transition::Cleared,
+                        _ => continue,
+                    }
+                } else {
+                    match record.active {
+                        false if value < limit => Transition::Raised,
+                        true if value >= limit + limits.hysteresis => Transition::Cleared,
+                        _ => continue,
+                    }
+                };
+
+                record.active = transition == Transition::Raised;
+                record.value = Some(value);
+                if record.active {
+                    record.acknowledged = false;
+                }
+                if record.annunciated() {
+                    let alarm = Alarm {
+                        address: register.address,
+                        tag: tag.name.clone(),
+                        level: level.name(),
+                        severity: level.severity(),
+                        value,
+                        limit,
+                        unit: tag.unit.clone(),
+                    };
+                    outputs.push(AlarmOutput::Event(transition, alarm));
+                }
+                outputs.push(AlarmOutput::State(self.update(key, tag, limit, record)));
+            }
+        }
+        outputs
+    }
+
+    /// apply an operator command to one alarm, returning its new state.
+    /// acknowledging an alarm that isn't awaiting one changes nothing.
+    pub fn command(
+        &mut self,
+        map: &RegisterMap,
+        unit_id: u8,
+        tag_name: &str,
+        level: Level,
+        action: Action,
+        now_ms: u64,
+    ) -> Result<AlarmState, String> {
+        let (point, tag) = map
+            .find(unit_id, tag_name)
+            .ok_or_else(|| format!("no tag '{}' on unit {}", tag_name, unit_id))?;
+        let limit = tag
+            .limits
+            .and_then(|l| l.limit(level))
+            .ok_or_else(|| format!("tag '{}' has no {} limit", tag.name, level.name()))?;
+
+        let key = AlarmKey { point, level };
+        let mut record = self.alarms.get(&key).copied().unwrap_or_default();
+        match action {
+            Action::Acknowledge => record.acknowledged = true,
+            // isa-18.2 shelving is always time limited
+            Action::Shelve(0) => return Err(format!("shelving '{}' {} needs a duration", tag.name, level.name())),
+            Action::Shelve(duration_ms) => record.shelved_until_ms = Some(now_ms.saturating_add(duration_ms as u64)),
+            Action::Unshelve => record.shelved_until_ms = None,
+            Action::Suppress => record.suppressed = true,
+            Action::Unsuppress => record.suppressed = false,
+        }
+        Ok(self.update(key, tag, limit, record))
+    }
+
+    /// return alarms whose shelving time is up to service, oldest point
+    /// first so every instance publishes in the same order
+    pub fn expire_shelves(&mut self, map: &RegisterMap, now_ms: u64) -> Vec<AlarmState> {
+        let mut expired: Vec<AlarmKey> = self
+            .alarms
+            .iter()
+            .filter(|(_, r)| r.shelved_until_ms.is_some_and(|until| now_ms >= until))
+            .map(|(key, _)| *key)
+            .collect();
+        expired.sort_by_key(|k| (k.point.unit_id, k.point.table as u8, k.point.address, k.level as u8));
+
+        expired
+            .into_iter()
+            .filter_map(|key| {
+                let mut record = self.alarms.remove(&key)?;
+                record.shelved_until_ms = None;
+                let tag = map.get(&key.point)?;
+                let limit = tag.limits?.limit(key.level)?;
+                Some(self.update(key, tag, limit, record))
+            })
+            .collect()
+    }
+
+    /// store `record`, forgetting alarms back in their normal state,
+    /// and describe it for the retained state document
+    fn update(&mut self, key: AlarmKey, tag: &Tag, limit: f64, record: AlarmRecord) -> AlarmState {
+        if record.is_normal() {
+            self.alarms.remove(&key);
+        } else {
+            self.alarms.insert(key, record);
+        }
+        AlarmState {
+            unit_id: key.point.unit_id,
+            address: key.point.address,
+            tag: tag.name.clone(),
+            level: key.level.name(),
+            severity: key.level.severity(),
+            limit,
+            unit: tag.unit.clone(),
+            state: record.state(),
+            active: record.active,
+            acknowledged: record.acknowledged,
+            value: record.value,
+            shelved_until_ms: record.shelved_until_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::datatype::Value;
+    use crate::register_map::{Tag, Unmapped};
+
+    fn limits() -> Limits {
+        Limits {
+            hihi: Some(120.0),
+            hi: Some(100.0),
+            lo: Some(20.0),
+            lolo: None,
+            hysteresis: 2.0,
+        }
+    }
+
+    fn map() -> RegisterMap {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+        let tag = Tag {
+            unit: Some("degC".to_string()),
+            limits: Some(limits()),
+            ..Tag::named("boiler_temp")
+        };
+        let key = PointKey { unit_id: 1, table: Table::HoldingRegister, address: 100 };
+        map.insert(key, tag).unwrap();
+        map
+    }
+
+    fn register(value: Value, label: Option<&str>) -> Register {
+        Register {
+            address: 100,
+            value,
+            data_type: None,
+            label: label.map(str::to_string),
+            description: None,
+            unit: None,
+            raw: None,
+        }
+    }
+
+    fn evaluate(tracker: &mut AlarmTracker, map: &RegisterMap, value: f64) -> Vec<AlarmOutput> {
+        let registers = [register(Value::Float(value), Some("boiler_temp"))];
+        tracker.evaluate(map, 1, Table::HoldingRegister, &registers)
+    }
+
+    /// (event, level) for every transition one sample announces
+    fn sample(tracker: &mut AlarmTracker, map: &RegisterMap, value: f64) -> Vec<(&'static str, &'static str)> {
+        evaluate(tracker, map, value)
+            .into_iter()
+            .filter_map(|output| match output {
+                AlarmOutput::Event(transition, alarm) => Some((transition.event(), alarm.level)),
+                AlarmOutput::State(_) => None,
+            })
+            .collect()
+    }
+
+    /// (level, state) for every alarm state one sample changes
+    fn states(tracker: &mut AlarmTracker, map: &RegisterMap, value: f64) -> Vec<(&'static str, &'static str)> {
+        evaluate(tracker, map, value)
+            .into_iter()
+            .filter_map(|output| match output {
+                AlarmOutput::State(state) => Some((state.level, state.state)),
+                AlarmOutput::Event(..) => None,
+            })
+            .collect()
+    }
+
+    fn command(tracker: &mut AlarmTracker, map: &RegisterMap, action: Action, now_ms: u64) -> Result<&'static str, String> {
+        tracker
+            .command(map, 1, "boiler_temp", Level::Hi, action, now_ms)
+            .map(|state| state.state)
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(limits().validate(), Ok(()));
+        assert!(Limits { hi: Some(130.0), ..limits() }.validate().is_err());
+        assert!(Limits { lolo: Some(25.0), ..limits() }.validate().is_err());
+        assert!(Limits { hysteresis: -1.0, ..limits() }.validate().is_err());
+        assert!(Limits { hi: Some(f64::NAN), ..limits() }.validate().is_err());
+        let none = Limits { hihi: None, hi: None, lo: None, lolo: None, hysteresis: 0.0 };
+        assert!(none.validate().is_err());
+    }
+
+    #[test]
+    fn test_raise_and_clear_with_hysteresis() {
+        let map = map();
+        let mut tracker = AlarmTracker::new();
+
+        assert!(sample(&mut tracker, &map, 50.0).is_empty());
+        // at the limit is not past it
+        assert!(sample(&mut tracker, &map, 100.0).is_empty());
+        assert_eq!(sample(&mut tracker, &map, 101.0), vec![("alarm_raised", "hi")]);
+        // still active - no repeat
+        assert!(sample(&mut tracker, &map, 105.0).is_empty());
+        // inside the hysteresis band
+        assert!(sample(&mut tracker, &map, 99.0).is_empty());
+        assert_eq!(sample(&mut tracker, &map, 98.0), vec![("alarm_cleared", "hi")]);
+
+        assert_eq!(sample(&mut tracker, &map, 19.5), vec![("alarm_raised", "lo")]);
+        assert!(sample(&mut tracker, &map, 21.0).is_empty());
+        assert_eq!(sample(&mut tracker, &map, 22.0), vec![("alarm_cleared", "lo")]);
+    }
+
+    #[test]
+    fn test_each_limit_is_its_own_alarm() {
+        let map = map();
+        let mut tracker = AlarmTracker::new();
+
+        assert_eq!(
+            sample(&mut tracker, &map, 125.0),
+            vec![("alarm_raised", "hi"), ("alarm_raised", "hihi")]
+        );
+        assert_eq!(sample(&mut tracker, &map, 110.0), vec![("alarm_cleared", "hihi")]);
+        assert_eq!(sample(&mut tracker, &map, 50.0), vec![("alarm_cleared", "hi")]);
+    }
+
+    #[test]
+    fn test_event_carries_value_and_limit() {
+        let map = map();
+        let mut tracker = AlarmTracker::new();
+        let registers = [register(Value::Unsigned(130), Some("boiler_temp"))];
+
+        let outputs = tracker.evaluate(&map, 1, Table::HoldingRegister, &registers);
+        let AlarmOutput::Event(_, alarm) = &outputs[2] else {
+            panic!("expected the hihi event, got {:?}", outputs[2]);
+        };
+        assert_eq!(alarm.tag, "boiler_temp");
+        assert_eq!((alarm.level, alarm.severity), ("hihi", "critical"));
+        assert_eq!((alarm.value, alarm.limit), (130.0, 120.0));
+        assert_eq!(alarm.unit.as_deref(), Some("degC"));
+    }
+
+    #[test]
+    fn test_ignores_nan_and_untagged() {
+        let map = map();
+        let mut tracker = AlarmTracker::new();
+
+        assert!(sample(&mut tracker, &map, f64::NAN).is_empty());
+        // an uncorrelated response's offset 100 is not the tagged point
+        let registers = [register(Value::Unsigned(500), None)];
+        assert!(tracker.evaluate(&map, 1, Table::HoldingRegister, &registers).is_empty());
+        // nor is another slave's
+        let registers = [register(Value::Unsigned(500), Some("boiler_temp"))];
+        assert!(tracker.evaluate(&map, 2, Table::HoldingRegister, &registers).is_empty());
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let map = map();
+        let mut tracker = AlarmTracker::new();
+
+        assert_eq!(states(&mut tracker, &map, 101.0), vec![("hi", "unacknowledged")]);
+        assert_eq!(command(&mut tracker, &map, Action::Acknowledge, 0), Ok("acknowledged"));
+        // acknowledged then cleared is back to normal
+        assert_eq!(states(&mut tracker, &map, 50.0), vec![("hi", "normal")]);
+
+        // cleared before anyone acknowledged it
+        assert_eq!(states(&mut tracker, &map, 101.0), vec![("hi", "unacknowledged")]);
+        assert_eq!(states(&mut tracker, &map, 50.0), vec![("hi", "returned_unacknowledged")]);
+        // raised again before acknowledgement
+        assert_eq!(states(&mut tracker, &map, 101.0), vec![("hi", "unacknowledged")]);
+        assert_eq!(states(&mut tracker, &map, 50.0), vec![("hi", "returned_unacknowledged")]);
+        assert_eq!(command(&mut tracker, &map, Action::Acknowledge, 0), Ok("normal"));
+        // nothing left to acknowledge
+        assert_eq!(command(&mut tracker, &map, Action::Acknowledge, 0), Ok("normal"));
+        assert!(tracker.alarms.is_empty());
+    }
+
+    #[test]
+    fn test_shelved_alarms_are_tracked_but_not_announced() {
+        let map = map();
+        let mut tracker = AlarmTracker::new();
+
+        assert_eq!(command(&mut tracker, &map, Action::Shelve(60_000), 1_000), Ok("shelved"));
+        assert!(sample(&mut tracker, &map, 101.0).is_empty());
+        assert_eq!(states(&mut tracker, &map, 105.0), Vec::new());
+        assert!(tracker.expire_shelves(&map, 60_999).is_empty());
+
+        // back in service, still raised and never acknowledged
+        let expired = tracker.expire_shelves(&map, 61_000);
+        assert_eq!(expired.len(), 1);
+        assert_eq!((expired[0].state, expired[0].active), ("unacknowledged", true));
+        assert_eq!(sample(&mut tracker, &map, 50.0), vec![("alarm_cleared", "hi")]);
+
+        assert_eq!(command(&mut tracker, &map, Action::Shelve(60_000), 0), Ok("shelved"));
+        assert_eq!(command(&mut tracker, &map, Action::Unshelve, 0), Ok("returned_unacknowledged"));
+        assert!(command(&mut tracker, &map, Action::Shelve(0), 0).is_err());
+    }
+
+    #[test]
+    fn test_suppression() {
+        let map = map();
+        let mut tracker = AlarmTracker::new();
+
+        assert_eq!(command(&mut tracker, &map, Action::Suppress, 0), Ok("suppressed"));
+        // suppression outranks shelving
+        assert_eq!(command(&mut tracker, &map, Action::Shelve(1_000), 0), Ok("suppressed"));
+        assert_eq!(states(&mut tracker, &map, 101.0), vec![("hi", "suppressed")]);
+        assert!(sample(&mut tracker, &map, 50.0).is_empty());
+        assert_eq!(tracker.expire_shelves(&map, 1_000)[0].state, "suppressed");
+        assert_eq!(command(&mut tracker, &map, Action::Unsuppress, 0), Ok("returned_unacknowledged"));
+    }
+
+    #[test]
+    fn test_commands_name_a_configured_alarm() {
+        let map = map();
+        let mut tracker = AlarmTracker::new();
+
+        assert!(tracker.command(&map, 1, "boiler_temp", Level::LoLo, Action::Acknowledge, 0).is_err());
+        assert!(tracker.command(&map, 1, "feed_temp", Level::Hi, Action::Acknowledge, 0).is_err());
+        assert!(tracker.command(&map, 2, "boiler_temp", Level::Hi, Action::Acknowledge, 0).is_err());
+        assert!(tracker.alarms.is_empty());
+    }
+}
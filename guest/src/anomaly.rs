@@ -0,0 +1,322 @@
+// guest/src/anomaly.rs
+// process-value injection detection.
+// a compromised plc or a man in the middle can feed values that look
+// plausible to a limit alarm but are physically impossible: a tank level
+// jumping faster than any pump could move it, a sensor that never changes
+// while the process around it does, or a value far outside its own recent
+// history. these are security events, published apart from process alarms,
+// so the soc and the operators each see what they act on.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::mqtt::payload::{Anomaly, Register};
+use crate::register_map::{PointKey, RegisterMap, Table};
+
+/// longest rolling window an outlier check may keep
+pub const MAX_WINDOW: u16 = 1000;
+
+/// flag values far from the mean of the last `window` samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutlierCheck {
+    pub window: u16,  // samples, excluding the one being checked
+    pub sigma: f64,   // standard deviations from the mean
+}
+
+/// injection checks for one tag, in engineering units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnomalyChecks {
+    pub max_rate: Option<f64>,          // units per second
+    pub frozen_after_ms: Option<u32>,   // identical samples for this long
+    pub outlier: Option<OutlierCheck>,
+}
+
+impl AnomalyChecks {
+    /// reject empty, non-positive and non-finite checks
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_rate.is_none() && self.frozen_after_ms.is_none() && self.outlier.is_none() {
+            return Err("anomaly checks enable none of max rate, frozen value or outlier".to_string());
+        }
+        if let Some(rate) = self.max_rate {
+            if !rate.is_finite() || rate <= 0.0 {
+                return Err("max rate of change must be finite and positive".to_string());
+            }
+        }
+        if self.frozen_after_ms == Some(0) {
+            return Err("frozen value time must be positive".to_string());
+        }
+        if let Some(outlier) = self.outlier {
+            if outlier.window < 2 || outlier.window > MAX_WINDOW {
+                return Err(format!("outlier window must be 2-{} samples", MAX_WINDOW));
+            }
+            if !outlier.sigma.is_finite() || outlier.sigma <= 0.0 {
+                return Err("outlier sigma must be finite and positive".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// which check an anomaly failed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    RateOfChange,  // observed in units per second
+    FrozenValue,   // observed in milliseconds unchanged
+    Outlier,       // observed in standard deviations from the mean
+}
+
+impl Kind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RateOfChange => "rate_of_change",
+            Self::FrozenValue => "frozen_value",
+            Self::Outlier => "outlier",
+        }
+    }
+}
+
+/// recent samples of one checked point
+#[derive(Debug)]
+struct History {
+    last: f64,
+    last_ms: u64,
+    changed_ms: u64,        // when the value last differed
+    frozen_reported: bool,  // one event per frozen stretch
+    window: VecDeque<f64>,
+}
+
+/// sample history of every point with anomaly checks.
+/// bounded by the register map - one window per checked tag.
+#[derive(Debug, Default)]
+pub struct AnomalyDetector {
+    points: HashMap<PointKey, History>,
+}
+
+impl AnomalyDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// check tagged registers against their history, returning the
+    /// anomalies found. a point's first sample only starts its history, and
+    /// non-finite values are skipped, since they carry no magnitude to check.
+    pub fn evaluate(
+        &mut self,
+        map: &RegisterMap,
+        unit_id: u8,
+        table: Table,
+        registers: &[Register],
+        now_ms: u64,
+    ) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+        // unlabelled registers have no tag, or no real address
+        for register in registers.iter().filter(|r| r.label.is_some()) {
+            let point = PointKey { unit_id, table, address: register.address };
+            let Some(tag) = map.get(&point) else { continue };
+            let Some(checks) = tag.anomaly else { continue };
+            let Some(value) = register.value.as_f64().filter(|v| v.is_finite()) else {
+                continue;
+            };
+            let Some(history) = self.points.get_mut(&point) else {
+                self.points.insert(point, History {
+                    last: value,
+                    last_ms: now_ms,
+                    changed_ms: now_ms,
+                    frozen_reported: false,
+                    window: VecDeque::from([value]),
+                });
+                continue;
+            };
+            let mut found = |kind: Kind, observed: f64, threshold: f64| {
+                anomalies.push(Anomaly {
+                    address: register.address,
+                    tag: tag.name.clone(),
+                    kind: kind.name(),
+                    value,
+                    observed,
+                    threshold,
+                    unit: tag.unit.clone(),
+                });
+            };
+
+            // a clock that went backwards gives no interval to measure
+            if let (Some(max_rate), true) = (checks.max_rate, now_ms >= history.last_ms) {
+                // samples within the same millisecond count as 1ms apart
+                let seconds = (now_ms - history.last_ms).max(1) as f64 / 1000.0;
+                let rate = (value - history.last).abs() / seconds;
+                if rate > max_rate {
+                    found(Kind::RateOfChange, rate, max_rate);
+                }
+            }
+
+            if value != history.last || now_ms < history.changed_ms {
+                history.changed_ms = now_ms;
+                history.frozen_reported = false;
+            } else if let Some(frozen_after_ms) = checks.frozen_after_ms {
+                let frozen_ms = now_ms - history.changed_ms;
+                if !history.frozen_reported && frozen_ms >= frozen_after_ms as u64 {
+                    history.frozen_reported = true;
+                    found(Kind::FrozenValue, frozen_ms as f64, frozen_after_ms as f64);
+                }
+            }
+
+            if let Some(outlier) = checks.outlier {
+                // a window without spread gives no scale to measure against
+                if history.window.len() >= outlier.window as usize {
+                    let (mean, deviation) = mean_deviation(&history.window);
+                    let sigmas = (value - mean).abs() / deviation;
+                    if deviation > 0.0 && sigmas > outlier.sigma {
+                        found(Kind::Outlier, sigmas, outlier.sigma);
+                    }
+                }
+                history.window.push_back(value);
+                while history.window.len() > outlier.window as usize {
+                    history.window.pop_front();
+                }
+            }
+
+            history.last = value;
+            history.last_ms = now_ms;
+        }
+        anomalies
+    }
+}
+
+/// mean and sample standard deviation of at least two samples
+fn mean_deviation(samples: &VecDeque<f64>) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let variance = samples.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::datatype::Value;
+    use crate::register_map::{Tag, Unmapped};
+
+    fn checks() -> AnomalyChecks {
+        AnomalyChecks {
+            max_rate: None,
+            frozen_after_ms: None,
+            outlier: None,
+        }
+    }
+
+    fn map(checks: AnomalyChecks) -> RegisterMap {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+        let tag = Tag {
+            unit: Some("m".to_string()),
+            anomaly: Some(checks),
+            ..Tag::named("tank_level")
+        };
+        let key = PointKey { unit_id: 1, table: Table::HoldingRegister, address: 10 };
+        map.insert(key, tag).unwrap();
+        map
+    }
+
+    /// (kind, observed) for every anomaly one sample raises
+    fn sample(detector: &mut AnomalyDetector, map: &RegisterMap, value: f64, now_ms: u64) -> Vec<(&'static str, f64)> {
+        let registers = [Register {
+            address: 10,
+            value: Value::Float(value),
+            data_type: None,
+            label: Some("tank_level".to_string()),
+            description: None,
+            unit: None,
+            raw: None,
+        }];
+        detector
+            .evaluate(map, 1, Table::HoldingRegister, &registers, now_ms)
+            .into_iter()
+            .map(|a| (a.kind, a.observed))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(checks().validate().is_err());
+        assert_eq!(AnomalyChecks { max_rate: Some(0.5), ..checks() }.validate(), Ok(()));
+        assert!(AnomalyChecks { max_rate: Some(0.0), ..checks() }.validate().is_err());
+        assert!(AnomalyChecks { frozen_after_ms: Some(0), ..checks() }.validate().is_err());
+        let outlier = |window, sigma| AnomalyChecks { outlier: Some(OutlierCheck { window, sigma }), ..checks() };
+        assert_eq!(outlier(10, 3.0).validate(), Ok(()));
+        assert!(outlier(1, 3.0).validate().is_err());
+        assert!(outlier(MAX_WINDOW + 1, 3.0).validate().is_err());
+        assert!(outlier(10, f64::NAN).validate().is_err());
+    }
+
+    #[test]
+    fn test_rate_of_change() {
+        let map = map(AnomalyChecks { max_rate: Some(0.5), ..checks() });
+        let mut detector = AnomalyDetector::new();
+
+        assert!(sample(&mut detector, &map, 4.0, 0).is_empty());
+        // 0.5 m in one second is the most the pumps can manage
+        assert!(sample(&mut detector, &map, 4.5, 1_000).is_empty());
+        assert!(sample(&mut detector, &map, 4.0, 2_000).is_empty());
+        assert_eq!(sample(&mut detector, &map, 6.0, 3_000), vec![("rate_of_change", 2.0)]);
+        // same millisecond - measured over 1ms rather than dividing by zero
+        assert_eq!(sample(&mut detector, &map, 6.001, 3_000).len(), 1);
+        // clock stepped backwards - nothing to measure
+        assert!(sample(&mut detector, &map, 9.0, 1_000).is_empty());
+    }
+
+    #[test]
+    fn test_frozen_value() {
+        let map = map(AnomalyChecks { frozen_after_ms: Some(10_000), ..checks() });
+        let mut detector = AnomalyDetector::new();
+
+        assert!(sample(&mut detector, &map, 4.2, 0).is_empty());
+        assert!(sample(&mut detector, &map, 4.2, 9_999).is_empty());
+        assert_eq!(sample(&mut detector, &map, 4.2, 10_000), vec![("frozen_value", 10_000.0)]);
+        // reported once per frozen stretch
+        assert!(sample(&mut detector, &map, 4.2, 20_000).is_empty());
+        assert!(sample(&mut detector, &map, 4.3, 21_000).is_empty());
+        assert!(sample(&mut detector, &map, 4.3, 30_000).is_empty());
+        assert_eq!(sample(&mut detector, &map, 4.3, 31_000).len(), 1);
+    }
+
+    #[test]
+    fn test_outlier() {
+        let map = map(AnomalyChecks { outlier: Some(OutlierCheck { window: 4, sigma: 3.0 }), ..checks() });
+        let mut detector = AnomalyDetector::new();
+
+        // window of 4, 5, 4, 5: mean 4.5, deviation ~0.577
+        for (i, value) in [4.0, 5.0, 4.0, 5.0].into_iter().enumerate() {
+            assert!(sample(&mut detector, &map, value, i as u64).is_empty());
+        }
+        assert!(sample(&mut detector, &map, 6.0, 4).is_empty());
+        let found = sample(&mut detector, &map, 20.0, 5);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, "outlier");
+        assert!(found[0].1 > 3.0);
+    }
+
+    #[test]
+    fn test_outlier_needs_a_full_window_with_spread() {
+        let map = map(AnomalyChecks { outlier: Some(OutlierCheck { window: 3, sigma: 2.0 }), ..checks() });
+        let mut detector = AnomalyDetector::new();
+
+        assert!(sample(&mut detector, &map, 4.0, 0).is_empty());
+        assert!(sample(&mut detector, &map, 90.0, 1).is_empty());
+        // a flat window has no scale - a setpoint change is not an attack
+        let mut detector = AnomalyDetector::new();
+        for i in 0..4 {
+            assert!(sample(&mut detector, &map, 4.0, i).is_empty());
+        }
+        assert!(sample(&mut detector, &map, 8.0, 4).is_empty());
+    }
+
+    #[test]
+    fn test_non_finite_and_untagged_are_skipped() {
+        let map = map(AnomalyChecks { max_rate: Some(0.5), ..checks() });
+        let mut detector = AnomalyDetector::new();
+
+        assert!(sample(&mut detector, &map, f64::NAN, 0).is_empty());
+        assert!(sample(&mut detector, &map, 4.0, 1_000).is_empty());
+        assert!(sample(&mut detector, &map, f64::INFINITY, 2_000).is_empty());
+        assert!(sample(&mut detector, &map, 4.5, 2_000).is_empty());
+        assert!(detector.evaluate(&map, 2, Table::HoldingRegister, &[], 3_000).is_empty());
+    }
+}
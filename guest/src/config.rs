@@ -0,0 +1,256 @@
+// guest/src/config.rs
+// gateway configuration fetched from the host through the wit config import.
+// loaded once, on the first call into the guest, and cached for the life of
+// the instance, so the host cannot change policy mid-stream and every frame
+// sees the same rules. loading before any frame is looked at also reports
+// rejected settings as soon as the gateway starts, not on its first frame.
+
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+use crate::alarms::Limits;
+use crate::anomaly::{AnomalyChecks, OutlierCheck};
+use crate::gateway::protocols::config as wit;
+use crate::metrics_impl::MetricsTracker;
+use crate::policy::{Action, Policy, Rule};
+use crate::poll::{PollEntry, PollSchedule, DEFAULT_TIMEOUT_MS};
+use crate::modbus::datatype::{ByteOrder, DataType};
+use crate::mqtt::sparkplug::EdgeNode;
+use crate::mqtt::topic::{Kind, Topics};
+use crate::mqtt::{PublishSettings, TelemetryFormat};
+use crate::register_map::{PointKey, RegisterMap, Scaling, Table, Tag, Unmapped};
+use crate::report::Deadband;
+use crate::status_bits::{BitField, StatusBits};
+
+/// everything the guest reads from the host at startup
+#[derive(Debug, Default)]
+pub struct GatewayConfig {
+    pub policy: Policy,
+    pub schedule: PollSchedule,
+    pub register_map: RegisterMap,
+    pub telemetry_format: TelemetryFormat,
+    pub topics: Topics,
+    pub publish: PublishSettings,
+}
+
+thread_local! {
+    static CONFIG: OnceCell<Rc<GatewayConfig>> = const { OnceCell::new() };
+}
+
+/// the cached configuration, loading it from the host on first call
+pub fn get() -> Rc<GatewayConfig> {
+    CONFIG.with(|c| c.get_or_init(|| Rc::new(fetch())).clone())
+}
+
+/// load the configuration if this is the first call into the guest -
+/// the gateway and metrics exports call it before anything else
+pub fn load() {
+    CONFIG.with(|c| {
+        c.get_or_init(|| Rc::new(fetch()));
+    });
+}
+
+fn fetch() -> GatewayConfig {
+    let register_map = register_map_from_wit(wit::get_register_map());
+    // {tag} expands to at most the longest tag name
+    let topics = topics_from_wit(wit::get_topic_templates(), register_map.longest_name());
+    GatewayConfig {
+        policy: policy_from_wit(wit::get_function_policy()),
+        schedule: schedule_from_wit(wit::get_poll_schedule()),
+        register_map,
+        telemetry_format: telemetry_format_from_wit(wit::get_telemetry_format()),
+        topics,
+        publish: publish_settings_from_wit(wit::get_publish_settings()),
+    }
+}
+
+fn action_from_wit(action: wit::PolicyAction) -> Action {
+    match action {
+        wit::PolicyAction::Allow => Action::Allow,
+        wit::PolicyAction::Deny => Action::Deny,
+    }
+}
+
+fn policy_from_wit(policy: wit::FunctionPolicy) -> Policy {
+    Policy {
+        rules: policy
+            .rules
+            .into_iter()
+            .filter_map(|r| {
+                // an inverted range would load and silently never match
+                if let Some(range) = r.address_range.filter(|a| a.start > a.end) {
+                    let msg = format!("policy rule '{}' dropped: address range {}-{} is inverted", r.name, range.start, range.end);
+                    MetricsTracker::record_config_error(msg);
+                    return None;
+                }
+                Some(Rule {
+                    name: r.name,
+                    unit_id: r.unit_id,
+                    function_code: r.function_code,
+                    address_range: r.address_range.map(|a| (a.start, a.end)),
+                    action: action_from_wit(r.action),
+                })
+            })
+            .collect(),
+        default_action: action_from_wit(policy.default_action),
+    }
+}
+
+fn schedule_from_wit(schedule: wit::PollSchedule) -> PollSchedule {
+    let entries = schedule
+        .entries
+        .into_iter()
+        .filter_map(|e| {
+            let name = e.name.clone();
+            PollEntry::new(e.name, e.unit_id, e.function_code, e.start_address, e.quantity, e.interval_ms)
+                .map_err(|msg| {
+                    let msg = format!("poll entry '{}' dropped: {}", name, msg);
+                    MetricsTracker::record_config_error(msg)
+                })
+                .ok()
+        })
+        .collect();
+    PollSchedule {
+        entries,
+        timeout_ms: if schedule.timeout_ms == 0 { DEFAULT_TIMEOUT_MS } else { schedule.timeout_ms },
+    }
+}
+
+fn register_map_from_wit(map: wit::RegisterMap) -> RegisterMap {
+    let mut register_map = RegisterMap::new(match map.unmapped {
+        wit::UnmappedPoints::PassThrough => Unmapped::PassThrough,
+        wit::UnmappedPoints::Drop => Unmapped::Drop,
+    });
+    register_map.include_raw = map.include_raw;
+    register_map.max_silence_ms = map.max_silence_ms.map(u64::from);
+    for t in map.tags {
+        let key = PointKey {
+            unit_id: t.unit_id,
+            table: match t.table {
+                wit::PointTable::Coil => Table::Coil,
+                wit::PointTable::DiscreteInput => Table::DiscreteInput,
+                wit::PointTable::InputRegister => Table::InputRegister,
+                wit::PointTable::HoldingRegister => Table::HoldingRegister,
+            },
+            address: t.address,
+        };
+        let tag = Tag {
+            name: t.tag,
+            description: t.description,
+            unit: t.unit,
+            data_type: t.data_type.map_or(DataType::Uint16, data_type_from_wit),
+            byte_order: t.byte_order.map_or(ByteOrder::Abcd, byte_order_from_wit),
+            scaling: t.scaling.map(|s| Scaling {
+                gain: s.gain,
+                offset: s.offset,
+                min: s.min,
+                max: s.max,
+            }),
+            status_bits: t.status_bits.map(|s| StatusBits {
+                fields: s
+                    .fields
+                    .into_iter()
+                    .map(|f| BitField { bit: f.bit, name: f.name })
+                    .collect(),
+                replace_word: s.replace_word,
+            }),
+            deadband: t.deadband.map(|d| match d {
+                wit::Deadband::Absolute(width) => Deadband::Absolute(width),
+                wit::Deadband::Percent(width) => Deadband::Percent(width),
+            }),
+            limits: t.limits.map(|l| Limits {
+                hihi: l.hihi,
+                hi: l.hi,
+                lo: l.lo,
+                lolo: l.lolo,
+                hysteresis: l.hysteresis,
+            }),
+            anomaly: t.anomaly.map(|a| AnomalyChecks {
+                max_rate: a.max_rate,
+                frozen_after_ms: a.frozen_after_ms,
+                outlier: a.outlier.map(|o| OutlierCheck { window: o.window, sigma: o.sigma }),
+            }),
+        };
+        if let Err(msg) = register_map.insert(key, tag) {
+            MetricsTracker::record_config_error(format!("register tag dropped: {}", msg));
+        }
+    }
+    register_map
+}
+
+fn telemetry_format_from_wit(format: wit::TelemetryFormat) -> TelemetryFormat {
+    match format {
+        wit::TelemetryFormat::Json => TelemetryFormat::Json,
+        wit::TelemetryFormat::SparkplugB(n) => {
+            let node = EdgeNode { group_id: n.group_id, node_id: n.edge_node_id };
+            match node.validate() {
+                Ok(()) => TelemetryFormat::SparkplugB(node),
+                Err(msg) => {
+                    let msg = format!("telemetry format falls back to json: {}", msg);
+                    MetricsTracker::record_config_error(msg);
+                    TelemetryFormat::Json
+                }
+            }
+        }
+    }
+}
+
+fn publish_settings_from_wit(settings: wit::PublishSettings) -> PublishSettings {
+    // mqtt 5 strings cannot carry nul
+    let mut gateway_id = settings.gateway_id;
+    if gateway_id.contains('\0') {
+        let msg = "gateway id left out of user properties: it contains nul".to_string();
+        MetricsTracker::record_config_error(msg);
+        gateway_id.clear();
+    }
+    PublishSettings {
+        gateway_id,
+        telemetry_expiry_secs: settings.telemetry_expiry_secs,
+    }
+}
+
+fn topics_from_wit(templates: wit::TopicTemplates, longest_tag: usize) -> Topics {
+    let configured = [
+        (Kind::Telemetry, templates.telemetry),
+        (Kind::Events, templates.events),
+        (Kind::Alarms, templates.alarms),
+        (Kind::AlarmState, templates.alarm_state),
+        (Kind::Security, templates.security),
+        (Kind::Status, templates.status),
+        (Kind::Devices, templates.devices),
+    ];
+    let configured = configured
+        .into_iter()
+        .filter_map(|(kind, text)| text.map(|text| (kind, text)))
+        .collect();
+    let (topics, errors) = Topics::new(templates.site, templates.area, configured, longest_tag);
+    for msg in errors {
+        MetricsTracker::record_config_error(msg);
+    }
+    topics
+}
+
+fn data_type_from_wit(data_type: wit::DataType) -> DataType {
+    match data_type {
+        wit::DataType::Uint16 => DataType::Uint16,
+        wit::DataType::Int16 => DataType::Int16,
+        wit::DataType::Uint32 => DataType::Uint32,
+        wit::DataType::Int32 => DataType::Int32,
+        wit::DataType::Uint64 => DataType::Uint64,
+        wit::DataType::Int64 => DataType::Int64,
+        wit::DataType::Float32 => DataType::Float32,
+        wit::DataType::Float64 => DataType::Float64,
+        wit::DataType::Bcd16 => DataType::Bcd16,
+        wit::DataType::Bcd32 => DataType::Bcd32,
+        wit::DataType::Ascii(registers) => DataType::Ascii(registers),
+    }
+}
+
+fn byte_order_from_wit(order: wit::ByteOrder) -> ByteOrder {
+    match order {
+        wit::ByteOrder::Abcd => ByteOrder::Abcd,
+        wit::ByteOrder::Cdab => ByteOrder::Cdab,
+        wit::ByteOrder::Badc => ByteOrder::Badc,
+        wit::ByteOrder::Dcba => ByteOrder::Dcba,
+    }
+}
@@ -0,0 +1,268 @@
+// guest/src/error.rs
+// structured gateway errors exported over wit in metrics::gateway-stats.
+// FrameError says exactly what check failed; GatewayError adds the class the
+// dashboard groups on, where in the frame as received the fault sits, and the
+// first bytes of the frame, so the host never has to match on message text.
+
+use std::fmt;
+
+use crate::modbus::adu::Framing;
+use crate::modbus::frame::HEADER_LEN;
+use crate::modbus::function::FunctionCode;
+use crate::modbus::validate::FrameError;
+
+/// leading frame bytes kept with an error - enough for any header plus the
+/// function code and first data fields, without copying whole frames
+pub const PREFIX_LEN: usize = 16;
+
+/// where in a rejected frame the fault was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameFault {
+    pub offset: usize,    // byte offset into the frame as received
+    pub prefix: Vec<u8>,  // first PREFIX_LEN bytes of the frame
+    pub detail: String,
+}
+
+/// why the gateway rejected a frame or failed to move it
+#[derive(Debug, Clone, PartialEq)]
+pub enum GatewayError {
+    TruncatedHeader(FrameFault),    // too short for the framing fields
+    BadProtocolId(FrameFault),      // mbap protocol id is not modbus
+    BadLength(FrameFault),          // length field or frame size is wrong
+    BadChecksum(FrameFault),        // rtu crc or ascii lrc failed
+    BadFraming(FrameFault),         // serial address, delimiters or hex encoding
+    IllegalFunction(FrameFault),    // function code the gateway does not implement
+    ByteCountMismatch(FrameFault),  // byte count disagrees with the data
+    MalformedPdu(FrameFault),       // known function, fields don't parse
+    InvalidRequest(FrameFault),     // request quantity or address outside spec
+    ResponseMismatch(FrameFault),   // response does not answer its request
+    StreamResync(u64),              // bytes discarded to find the next frame
+    ReceiveFailure(String),         // modbus-source returned an error
+    PublishFailure(String),         // mqtt-sink returned an error
+    PollFailure(String),            // modbus-client got no reply
+    InvalidConfig(String),          // host config entry rejected at load
+    InvalidCommand(String),         // inbound operator command rejected
+}
+
+impl GatewayError {
+    /// error from stripping the framing (adu::decode)
+    pub fn framing(err: &FrameError, framing: Framing, frame: &[u8]) -> Self {
+        let len = frame.len();
+        let offset = match *err {
+            FrameError::TruncatedHeader => len,
+            FrameError::CrcMismatch { .. } => len.saturating_sub(2),
+            FrameError::LrcMismatch { .. } => len.saturating_sub(4),
+            FrameError::MissingEndDelimiter => len.saturating_sub(2),
+            FrameError::InvalidSlaveAddress(_) | FrameError::OddHexLength(_) => match framing {
+                Framing::Ascii => 1,
+                Framing::Tcp | Framing::Rtu => 0,
+            },
+            FrameError::InvalidHexCharacter { position, .. } => position,
+            FrameError::InvalidProtocolId(_) => 2,
+            FrameError::LengthOutOfRange(_) | FrameError::LengthExceedsFrame { .. } => 4,
+            // the length field counts from the unit id at offset 6
+            FrameError::TrailingBytes { expected, .. } => HEADER_LEN - 1 + expected,
+            _ => 0,
+        };
+        Self::classify(err, fault(frame, offset, err.to_string()))
+    }
+
+    /// error found inside the pdu (validation, request checks, correlation)
+    pub fn pdu(err: &FrameError, framing: Framing, frame: &[u8]) -> Self {
+        let offset = match *err {
+            FrameError::TruncatedPdu => pdu_end(framing, frame.len()),
+            FrameError::FunctionMismatch { .. } => pdu_offset(framing, 0),
+            FrameError::QuantityOutOfRange(_) => pdu_offset(framing, 3),
+            FrameError::TrailingBytes { expected, .. } => pdu_offset(framing, expected),
+            // byte count and start address both sit right after the function code
+            _ => pdu_offset(framing, 1),
+        };
+        Self::classify(err, fault(frame, offset, err.to_string()))
+    }
+
+    /// pdu the parser gave up on, `parsed` bytes in.
+    /// an unknown function code is an illegal function, anything else is malformed.
+    pub fn unparsed(framing: Framing, frame: &[u8], pdu: &[u8], parsed: usize) -> Self {
+        let function = pdu.first().copied().unwrap_or(0);
+        if FunctionCode::from_byte(function & 0x7F).is_none() {
+            let detail = format!("illegal function 0x{:02X}", function);
+            return Self::in_pdu(Self::IllegalFunction, framing, frame, 0, detail);
+        }
+        let detail = format!("malformed pdu at byte {}", parsed);
+        Self::in_pdu(Self::MalformedPdu, framing, frame, parsed, detail)
+    }
+
+    /// error of the given class at byte `offset` of the frame's pdu
+    pub fn in_pdu(class: fn(FrameFault) -> Self, framing: Framing, frame: &[u8], offset: usize, detail: String) -> Self {
+        class(fault(frame, pdu_offset(framing, offset), detail))
+    }
+
+    fn classify(err: &FrameError, fault: FrameFault) -> Self {
+        match err {
+            FrameError::TruncatedHeader => Self::TruncatedHeader(fault),
+            FrameError::InvalidProtocolId(_) => Self::BadProtocolId(fault),
+            FrameError::FrameTooLong(_)
+            | FrameError::LengthOutOfRange(_)
+            | FrameError::LengthExceedsFrame { .. }
+            | FrameError::TrailingBytes { .. }
+            | FrameError::TruncatedPdu => Self::BadLength(fault),
+            FrameError::CrcMismatch { .. } | FrameError::LrcMismatch { .. } => Self::BadChecksum(fault),
+            FrameError::InvalidSlaveAddress(_)
+            | FrameError::MissingStartDelimiter
+            | FrameError::MissingEndDelimiter
+            | FrameError::OddHexLength(_)
+            | FrameError::InvalidHexCharacter { .. } => Self::BadFraming(fault),
+            FrameError::ByteCountTooLarge(_)
+            | FrameError::EmptyResponse
+            | FrameError::OddByteCount(_)
+            | FrameError::ByteCountMismatch { .. } => Self::ByteCountMismatch(fault),
+            FrameError::QuantityOutOfRange(_) | FrameError::AddressOverflow { .. } => Self::InvalidRequest(fault),
+            FrameError::FunctionMismatch { .. } | FrameError::QuantityMismatch { .. } => Self::ResponseMismatch(fault),
+        }
+    }
+
+    /// the frame fault, for errors raised on a specific frame
+    pub fn fault(&self) -> Option<&FrameFault> {
+        match self {
+            Self::TruncatedHeader(f)
+            | Self::BadProtocolId(f)
+            | Self::BadLength(f)
+            | Self::BadChecksum(f)
+            | Self::BadFraming(f)
+            | Self::IllegalFunction(f)
+            | Self::ByteCountMismatch(f)
+            | Self::MalformedPdu(f)
+            | Self::InvalidRequest(f)
+            | Self::ResponseMismatch(f) => Some(f),
+            Self::StreamResync(_)
+            | Self::ReceiveFailure(_)
+            | Self::PublishFailure(_)
+            | Self::PollFailure(_)
+            | Self::InvalidConfig(_)
+            | Self::InvalidCommand(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StreamResync(count) => write!(f, "stream resync: discarded {} bytes", count),
+            Self::ReceiveFailure(msg) => write!(f, "receive error: {}", msg),
+            Self::PublishFailure(msg) => write!(f, "mqtt publish error: {}", msg),
+            Self::PollFailure(msg) => write!(f, "poll failed: {}", msg),
+            Self::InvalidConfig(msg) => write!(f, "invalid config: {}", msg),
+            Self::InvalidCommand(msg) => write!(f, "invalid command: {}", msg),
+            other => match other.fault() {
+                Some(fault) => write!(f, "{} (at byte {})", fault.detail, fault.offset),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+fn fault(frame: &[u8], offset: usize, detail: String) -> FrameFault {
+    FrameFault {
+        offset,
+        prefix: frame[..frame.len().min(PREFIX_LEN)].to_vec(),
+        detail,
+    }
+}
+
+/// offset in the received frame of byte `offset` of the pdu
+fn pdu_offset(framing: Framing, offset: usize) -> usize {
+    match framing {
+        Framing::Tcp => HEADER_LEN + offset,
+        Framing::Rtu => 1 + offset,
+        // ':' then two hex characters per byte of address + pdu
+        Framing::Ascii => 1 + 2 * (1 + offset),
+    }
+}
+
+/// offset in the received frame just past the pdu
+fn pdu_end(framing: Framing, frame_len: usize) -> usize {
+    match framing {
+        Framing::Tcp => frame_len,
+        Framing::Rtu => frame_len.saturating_sub(2),
+        Framing::Ascii => frame_len.saturating_sub(4),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::adu;
+
+    fn decode_error(framing: Framing, frame: &[u8]) -> GatewayError {
+        GatewayError::framing(&adu::decode(framing, frame).unwrap_err(), framing, frame)
+    }
+
+    #[test]
+    fn test_tcp_header_faults() {
+        let err = decode_error(Framing::Tcp, &[0x00, 0x01, 0x00]);
+        assert!(matches!(&err, GatewayError::TruncatedHeader(f) if f.offset == 3 && f.prefix == [0x00, 0x01, 0x00]));
+
+        // the wrongProtocol chaos attack
+        let frame = [0x00, 0x01, 0xDE, 0xAD, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A];
+        let err = decode_error(Framing::Tcp, &frame);
+        assert!(matches!(&err, GatewayError::BadProtocolId(f) if f.offset == 2));
+
+        // the bufferOverflow chaos attack
+        let err = decode_error(Framing::Tcp, &[0x00, 0x01, 0x00, 0x00, 0x00, 0xFF, 0x01, 0x03]);
+        assert!(matches!(&err, GatewayError::BadLength(f) if f.offset == 4));
+
+        // length 3 covers unit + 2 pdu bytes, so the extra bytes start at 9
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x01, 0x03, 0x00, 0x00, 0x00];
+        let err = decode_error(Framing::Tcp, &frame);
+        assert!(matches!(&err, GatewayError::BadLength(f) if f.offset == 9));
+    }
+
+    #[test]
+    fn test_serial_faults() {
+        let mut frame = vec![0x11, 0x03, 0x00, 0x6B, 0x00, 0x03, 0x76, 0x87];
+        frame[7] ^= 0xFF;
+        let err = decode_error(Framing::Rtu, &frame);
+        assert!(matches!(&err, GatewayError::BadChecksum(f) if f.offset == 6));
+
+        let err = decode_error(Framing::Ascii, b":1103006B00037F\r\n");
+        assert!(matches!(&err, GatewayError::BadChecksum(f) if f.offset == 13));
+
+        let err = decode_error(Framing::Ascii, b":1103006b00037E\r\n");
+        assert!(matches!(&err, GatewayError::BadFraming(f) if f.offset == 8));
+    }
+
+    #[test]
+    fn test_pdu_faults_are_located_in_the_frame() {
+        // register response with byte count 4 but only 2 data bytes
+        let pdu = [0x03, 0x04, 0x00, 0x01];
+        let err = crate::modbus::validate::validate_pdu(&pdu).unwrap_err();
+
+        let tcp = GatewayError::pdu(&err, Framing::Tcp, &[0u8; 11]);
+        assert!(matches!(&tcp, GatewayError::ByteCountMismatch(f) if f.offset == 8));
+        let rtu = GatewayError::pdu(&err, Framing::Rtu, &[0u8; 7]);
+        assert!(matches!(&rtu, GatewayError::ByteCountMismatch(f) if f.offset == 2));
+        // ':' + address "01" + function "03" puts the byte count at 5
+        let ascii = GatewayError::pdu(&err, Framing::Ascii, &[0u8; 15]);
+        assert!(matches!(&ascii, GatewayError::ByteCountMismatch(f) if f.offset == 5));
+    }
+
+    #[test]
+    fn test_unparsed_pdu() {
+        // write single register - passes validate_pdu, no parser accepts it
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x06, 0x00, 0x01, 0x00, 0x03];
+        let err = GatewayError::unparsed(Framing::Tcp, &frame, &frame[7..], 0);
+        assert!(matches!(&err, GatewayError::IllegalFunction(f) if f.offset == 7));
+        assert_eq!(err.to_string(), "illegal function 0x06 (at byte 7)");
+
+        let err = GatewayError::unparsed(Framing::Tcp, &frame, &[0x2B, 0x0E, 0x01], 2);
+        assert!(matches!(&err, GatewayError::MalformedPdu(f) if f.offset == 9));
+    }
+
+    #[test]
+    fn test_prefix_is_bounded() {
+        let err = decode_error(Framing::Rtu, &[0u8; 300]);
+        assert!(matches!(&err, GatewayError::BadLength(f) if f.prefix.len() == PREFIX_LEN));
+        assert_eq!(err.fault().map(|f| f.offset), Some(0));
+        assert_eq!(GatewayError::StreamResync(3).fault(), None);
+    }
+}
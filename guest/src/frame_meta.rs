@@ -0,0 +1,62 @@
+// guest/src/frame_meta.rs
+// where and when the host received a frame. the source uri identifies the
+// plc a payload describes, which matters once one gateway serves several,
+// and the connection id names the socket or serial port it arrived on.
+// both can appear in topics, so they are bounded here, once per frame.
+// either may be empty - topics render an empty value as "_". the receive
+// time must fit an rfc 3339 timestamp, so year 9999 at the latest.
+
+use crate::timestamp;
+
+/// longest source uri or connection id accepted from the host
+pub const MAX_LEN: usize = 255;
+
+/// the host's description of one received frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameMeta {
+    pub source: String,         // e.g. "modbus://10.0.0.50:502"
+    pub connection_id: String,  // e.g. "plc-north"
+    pub received_ms: u64,       // milliseconds since the unix epoch
+}
+
+impl FrameMeta {
+    pub fn new(source: String, connection_id: String, received_ms: u64) -> Result<Self, String> {
+        for (what, value) in [("source uri", &source), ("connection id", &connection_id)] {
+            if value.len() > MAX_LEN {
+                return Err(format!("frame {} is {} bytes, over the {} byte limit", what, value.len(), MAX_LEN));
+            }
+        }
+        if received_ms > timestamp::MAX_MS {
+            return Err(format!("frame received at {} ms, after the year 9999", received_ms));
+        }
+        Ok(Self {
+            source,
+            connection_id,
+            received_ms,
+        })
+    }
+
+    /// receive time as an rfc 3339 timestamp
+    pub fn received_at(&self) -> String {
+        timestamp::rfc3339(self.received_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let meta = FrameMeta::new("modbus://10.0.0.50:502".to_string(), "plc-north".to_string(), 1_767_571_200_250).unwrap();
+        assert_eq!(meta.received_at(), "2026-01-05T00:00:00.250Z");
+
+        assert!(FrameMeta::new("a".repeat(MAX_LEN), String::new(), 0).is_ok());
+        assert!(FrameMeta::new("a".repeat(MAX_LEN + 1), String::new(), 0).is_err());
+        assert!(FrameMeta::new(String::new(), "c".repeat(MAX_LEN + 1), 0).is_err());
+
+        assert!(FrameMeta::new(String::new(), String::new(), 253_402_300_799_999).is_ok());
+        assert!(FrameMeta::new(String::new(), String::new(), 253_402_300_800_000).is_err());
+        assert!(FrameMeta::new(String::new(), String::new(), u64::MAX).is_err());
+    }
+}
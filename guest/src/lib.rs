@@ -1,41 +1,87 @@
 // guest/src/lib.rs
 // main entry point for the protocol gateway wasm component.
-// simplified version for initial build verification.
+// receives modbus frames from the host, checks them against the site policy,
+// correlates responses with tracked requests and decodes them through the
+// register map. the results go out over mqtt as json telemetry or sparkplug b,
+// on configurable topics, alongside limit alarms, anomaly events and status
+// bit changes. timestamps come from the wasi clocks.
 
 wit_bindgen::generate!({
     world: "protocol-gateway",
     path: "../wit",
+    // also generates the wasi clock bindings from wit/deps
+    generate_all,
 });
 
+mod alarms;
+mod anomaly;
+mod config;
+mod error;
+mod frame_meta;
+mod metrics_impl;
 mod modbus;
 mod mqtt;
+mod policy;
+mod poll;
+mod register_map;
+mod report;
+mod status_bits;
+mod timestamp;
 
-use modbus::{frame::MbapHeader, function::{FunctionCode, ReadResponse}};
-use mqtt::payload::{TelemetryPayload, Register};
+use alarms::{Action, AlarmOutput, AlarmTracker, Level, Transition};
+use anomaly::AnomalyDetector;
+use error::GatewayError;
+use frame_meta::FrameMeta;
+use metrics_impl::MetricsTracker;
+use modbus::{
+    adu::{self, Framing},
+    frame::MbapHeader,
+    function::{DeviceIdRequest, DeviceIdResponse, ExceptionResponse, FunctionCode, ReadRequest, ReadResponse, ResponsePdu},
+    inventory::{self, DeviceInventory},
+    stream::{StreamEvent, StreamReassembler},
+    transaction::{verify_response, Correlation, TransactionKey, TransactionTable},
+    validate::{validate_pdu, FrameError},
+};
+use mqtt::sparkplug::{self, Session};
+use mqtt::topic::{Kind, TopicValues};
+use mqtt::TelemetryFormat;
+use gateway::protocols::mqtt_sink::{self, PublishOptions};
+use mqtt::payload::{
+    self, Alarm, AlarmPayload, AlarmState, AlarmStatePayload, Anomaly, AnomalyPayload, BitChangePayload, DeviceIdentityPayload, ExceptionPayload, StatusBit, TelemetryData, TelemetryPayload,
+};
+use policy::Subject;
+use poll::{PollEntry, Scheduler};
+use register_map::{PointKey, Table};
+use report::ExceptionReporter;
+use status_bits::ChangeDetector;
 
-use std::cell::{Cell, RefCell};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 
-// metrics storage
+// requests waiting for a response, keyed by transaction id + unit id,
+// the partial frame carried between chunks of a tcp byte stream,
+// device identities still waiting on a "more follows" continuation,
+// when each scan-list read is next due, the last value of every named
+// status bit, the last published value of every deadbanded point, the
+// limit alarms not in their normal state, recent samples of every point
+// with anomaly checks, and the sparkplug session
 thread_local! {
-    static FRAMES_PROCESSED: Cell<u64> = Cell::new(0);
-    static FRAMES_INVALID: Cell<u64> = Cell::new(0);
-    static BYTES_IN: Cell<u64> = Cell::new(0);
-    static BYTES_OUT: Cell<u64> = Cell::new(0);
-    static LAST_ERROR: RefCell<Option<String>> = RefCell::new(None);
+    static TRANSACTIONS: RefCell<TransactionTable> = RefCell::new(TransactionTable::new());
+    static STREAM: RefCell<StreamReassembler> = RefCell::new(StreamReassembler::new());
+    static INVENTORY: RefCell<DeviceInventory> = RefCell::new(DeviceInventory::new());
+    static POLLER: RefCell<Scheduler> = RefCell::new(Scheduler::new());
+    static STATUS_BITS: RefCell<ChangeDetector> = RefCell::new(ChangeDetector::new());
+    static REPORTER: RefCell<ExceptionReporter> = RefCell::new(ExceptionReporter::new());
+    static ALARMS: RefCell<AlarmTracker> = RefCell::new(AlarmTracker::new());
+    static ANOMALIES: RefCell<AnomalyDetector> = RefCell::new(AnomalyDetector::new());
+    static SPARKPLUG: RefCell<Session> = RefCell::new(Session::new());
 }
 
-fn record_frame(size: u64) {
-    FRAMES_PROCESSED.with(|f| f.set(f.get() + 1));
-    BYTES_IN.with(|b| b.set(b.get() + size));
-}
-
-fn record_error(msg: String) {
-    FRAMES_INVALID.with(|f| f.set(f.get() + 1));
-    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
-}
-
-fn record_outbound(size: u64) {
-    BYTES_OUT.with(|b| b.set(b.get() + size));
+/// how the host delivers bytes from a modbus source
+#[derive(Clone, Copy)]
+enum SourceMode {
+    Framed(Framing),  // one complete frame per receive
+    Stream,           // arbitrary chunks of a modbus tcp byte stream
 }
 
 struct Component;
@@ -44,82 +90,873 @@ export!(Component);
 
 impl Guest for Component {
     fn run() {
-        // receive frame from host
-        let frame = match gateway::protocols::modbus_source::receive_frame() {
-            Ok(data) => data,
+        config::load();
+        handle_commands();
+        let mode = source_mode();
+        
+        // receive frame (or stream chunk) from host
+        let received = match gateway::protocols::modbus_source::receive_frame() {
+            Ok(received) => received,
             Err(e) => {
-                record_error(format!("receive error: {}", e.message));
+                // a failed stream read means the connection state is gone
+                if let SourceMode::Stream = mode {
+                    STREAM.with(|s| s.borrow_mut().reset());
+                }
+                MetricsTracker::record_error(GatewayError::ReceiveFailure(e.message));
                 return;
             }
         };
         
-        let frame_size = frame.len() as u64;
-        
-        // parse mbap header
-        let (remaining, header) = match MbapHeader::parse(&frame) {
-            Ok(result) => result,
-            Err(_) => {
-                record_error("malformed mbap header".to_string());
+        let meta = match frame_meta(received.meta) {
+            Ok(meta) => meta,
+            Err(msg) => {
+                // a dropped chunk leaves the stream as unusable as a failed read
+                if let SourceMode::Stream = mode {
+                    STREAM.with(|s| s.borrow_mut().reset());
+                }
+                MetricsTracker::record_error(GatewayError::ReceiveFailure(msg));
+                return;
+            }
+        };
+        match mode {
+            SourceMode::Framed(framing) => process_frame(framing, &received.data, &meta),
+            SourceMode::Stream => {
+                // a frame split across chunks was received with its last chunk
+                let events = STREAM.with(|s| s.borrow_mut().push(&received.data));
+                for event in events {
+                    match event {
+                        StreamEvent::Frame(frame) => process_frame(Framing::Tcp, &frame, &meta),
+                        StreamEvent::Discarded(count) => MetricsTracker::record_discarded(count as u64),
+                    }
+                }
+            }
+        }
+    }
+
+    fn track_request(frame: Vec<u8>) {
+        config::load();
+        let framing = request_framing();
+        let adu = match adu::decode(framing, &frame) {
+            Ok(adu) => adu,
+            Err(e) => {
+                MetricsTracker::record_error(GatewayError::framing(&e, framing, &frame));
                 return;
             }
         };
         
-        // validate header
-        if let Err(msg) = header.validate() {
-            record_error(msg.to_string());
+        // device identification carries no addresses - only its key is kept
+        if adu.pdu.first() == Some(&FunctionCode::ReadDeviceIdentification.to_byte()) {
+            match DeviceIdRequest::parse(&adu.pdu) {
+                Ok(([], _)) => {}
+                Ok((rest, _)) => {
+                    let e = FrameError::TrailingBytes { expected: 4, actual: 4 + rest.len() };
+                    MetricsTracker::record_error(GatewayError::pdu(&e, framing, &frame));
+                    return;
+                }
+                Err(e) => {
+                    let parsed = parsed_len(&adu.pdu, &e);
+                    MetricsTracker::record_error(GatewayError::unparsed(framing, &frame, &adu.pdu, parsed));
+                    return;
+                }
+            }
+            let subject = Subject {
+                unit_id: adu.key.unit_id,
+                function_code: FunctionCode::ReadDeviceIdentification.to_byte(),
+                range: None,
+            };
+            if let Err(denial) = config::get().policy.evaluate(subject) {
+                MetricsTracker::record_denied(denial.to_string());
+                return;
+            }
+            TRANSACTIONS.with(|t| t.borrow_mut().insert_identification(adu.key));
             return;
         }
         
-        // parse response
-        let response = match ReadResponse::parse(remaining) {
-            Ok((_, resp)) => resp,
-            Err(_) => {
-                record_error("malformed pdu".to_string());
+        let request = match ReadRequest::parse(&adu.pdu) {
+            Ok(([], request)) => request,
+            Ok((rest, _)) => {
+                let e = FrameError::TrailingBytes { expected: 5, actual: 5 + rest.len() };
+                MetricsTracker::record_error(GatewayError::pdu(&e, framing, &frame));
+                return;
+            }
+            Err(e) => {
+                let parsed = parsed_len(&adu.pdu, &e);
+                MetricsTracker::record_error(GatewayError::unparsed(framing, &frame, &adu.pdu, parsed));
                 return;
             }
         };
         
+        if let Err(e) = request.validate() {
+            MetricsTracker::record_error(GatewayError::pdu(&e, framing, &frame));
+            return;
+        }
+        
+        // a denied request is never tracked. its response stays unmatched,
+        // which address-scoped deny rules refuse
+        let subject = Subject {
+            unit_id: adu.key.unit_id,
+            function_code: request.function.to_byte(),
+            range: Some(request_range(&request)),
+        };
+        if let Err(denial) = config::get().policy.evaluate(subject) {
+            MetricsTracker::record_denied(denial.to_string());
+            return;
+        }
+        
+        TRANSACTIONS.with(|t| t.borrow_mut().insert(adu.key, request));
+    }
+
+    fn poll_due(now_ms: u64) {
+        let config = config::get();
+        handle_commands();
+        let framing = request_framing();
+        let due = POLLER.with(|p| p.borrow_mut().due(&config.schedule, now_ms));
+        for entry in due {
+            poll_entry(framing, entry, config.schedule.timeout_ms);
+        }
+    }
+}
+
+/// most commands taken per call, so a host whose queue never runs dry
+/// cannot stall frame processing
+const MAX_COMMANDS: usize = 64;
+
+/// apply queued operator commands and return expired shelves to service,
+/// publishing every alarm state that changes
+fn handle_commands() {
+    use gateway::protocols::inbound_commands::{self as wit, Command};
+    
+    let register_map = &config::get().register_map;
+    let now_ms = clock_ms();
+    for _ in 0..MAX_COMMANDS {
+        let Some(command) = wit::next_command() else {
+            break;
+        };
+        match command {
+            Command::Alarm(command) => apply_alarm_command(command, now_ms),
+            Command::Rebirth => rebirth(now_ms),
+        }
+    }
+    
+    let expired = ALARMS.with(|a| a.borrow_mut().expire_shelves(register_map, now_ms));
+    for state in expired {
+        publish_alarm_state(state, None);
+    }
+}
+
+/// acknowledge, shelve or suppress one alarm and publish its new state
+fn apply_alarm_command(command: gateway::protocols::inbound_commands::AlarmCommand, now_ms: u64) {
+    use gateway::protocols::inbound_commands as wit;
+    
+    let register_map = &config::get().register_map;
+    let level = match command.level {
+        wit::AlarmLevel::Lolo => Level::LoLo,
+        wit::AlarmLevel::Lo => Level::Lo,
+        wit::AlarmLevel::Hi => Level::Hi,
+        wit::AlarmLevel::Hihi => Level::HiHi,
+    };
+    let action = match command.action {
+        wit::AlarmAction::Acknowledge => Action::Acknowledge,
+        wit::AlarmAction::Shelve(duration_ms) => Action::Shelve(duration_ms),
+        wit::AlarmAction::Unshelve => Action::Unshelve,
+        wit::AlarmAction::Suppress => Action::Suppress,
+        wit::AlarmAction::Unsuppress => Action::Unsuppress,
+    };
+    let result = ALARMS.with(|a| {
+        a.borrow_mut().command(register_map, command.unit_id, &command.tag, level, action, now_ms)
+    });
+    match result {
+        Ok(state) => {
+            MetricsTracker::record_command();
+            publish_alarm_state(state, None);
+        }
+        Err(msg) => MetricsTracker::record_command_rejected(msg),
+    }
+}
+
+/// repeat the node and device births for a sparkplug host application
+fn rebirth(now_ms: u64) {
+    let config = config::get();
+    let TelemetryFormat::SparkplugB(node) = &config.telemetry_format else {
+        MetricsTracker::record_command_rejected("rebirth needs sparkplug b telemetry".to_string());
+        return;
+    };
+    MetricsTracker::record_command();
+    let outputs = SPARKPLUG.with(|s| s.borrow_mut().rebirth(node, &config.register_map, now_ms));
+    publish_sparkplug(outputs);
+}
+
+/// send one scan-list read through the host and process its reply
+/// like any other response frame
+fn poll_entry(framing: Framing, entry: &PollEntry, timeout_ms: u32) {
+    let subject = Subject {
+        unit_id: entry.unit_id,
+        function_code: entry.request.function.to_byte(),
+        range: Some(request_range(&entry.request)),
+    };
+    if let Err(denial) = config::get().policy.evaluate(subject) {
+        MetricsTracker::record_denied(denial.to_string());
+        return;
+    }
+    
+    // serial framings carry no transaction id, so replies decode with id 0
+    let transaction_id = match framing {
+        Framing::Tcp => POLLER.with(|p| p.borrow_mut().next_transaction_id()),
+        Framing::Rtu | Framing::Ascii => 0,
+    };
+    let key = TransactionKey {
+        transaction_id,
+        unit_id: entry.unit_id,
+    };
+    let frame = match adu::encode(framing, key, &entry.request.to_bytes()) {
+        Ok(frame) => frame,
+        Err(e) => {
+            MetricsTracker::record_poll_failure(format!("poll '{}' failed: {}", entry.name, e));
+            return;
+        }
+    };
+    
+    TRANSACTIONS.with(|t| t.borrow_mut().insert(key, entry.request.clone()));
+    MetricsTracker::record_poll();
+    
+    match gateway::protocols::modbus_client::transact(&frame, timeout_ms) {
+        Ok(reply) => match frame_meta(reply.meta) {
+            Ok(meta) => process_frame(framing, &reply.data, &meta),
+            Err(msg) => {
+                TRANSACTIONS.with(|t| t.borrow_mut().cancel(key));
+                MetricsTracker::record_error(GatewayError::ReceiveFailure(msg));
+            }
+        },
+        Err(e) => {
+            TRANSACTIONS.with(|t| t.borrow_mut().cancel(key));
+            MetricsTracker::record_poll_failure(format!("poll '{}' failed: {}", entry.name, e.message));
+            // a sparkplug device that stops answering is dead until it reports again
+            if let TelemetryFormat::SparkplugB(node) = &config::get().telemetry_format {
+                let death = SPARKPLUG.with(|s| s.borrow_mut().device_death(node, entry.unit_id, clock_ms()));
+                publish_sparkplug(death.into_iter().collect());
+            }
+        }
+    }
+}
+
+/// decode, correlate and publish one complete response frame
+fn process_frame(framing: Framing, frame: &[u8], meta: &FrameMeta) {
+    let frame_size = frame.len() as u64;
+    
+    // strip framing
+    let adu = match adu::decode(framing, frame) {
+        Ok(adu) => adu,
+        Err(e) => {
+            MetricsTracker::record_error(GatewayError::framing(&e, framing, frame));
+            return;
+        }
+    };
+    
+    // site policy, before any pdu parsing. the address range is only known
+    // from the tracked request this response answers.
+    if let Some(&function_byte) = adu.pdu.first() {
+        let range = TRANSACTIONS.with(|t| t.borrow().get(&adu.key).map(request_range));
+        let subject = Subject {
+            unit_id: adu.key.unit_id,
+            function_code: function_byte & 0x7F,
+            range,
+        };
+        if let Err(denial) = config::get().policy.evaluate(subject) {
+            MetricsTracker::record_denied(denial.to_string());
+            return;
+        }
+    }
+    
+    // check byte counts against the bytes received
+    if let Err(e) = validate_pdu(&adu.pdu) {
+        MetricsTracker::record_error(GatewayError::pdu(&e, framing, frame));
+        return;
+    }
+    
+    // parse response - exceptions are valid frames, not malformed ones
+    let response = match ResponsePdu::parse(&adu.pdu) {
+        Ok((_, pdu)) => pdu,
+        Err(e) => {
+            let parsed = parsed_len(&adu.pdu, &e);
+            MetricsTracker::record_error(GatewayError::unparsed(framing, frame, &adu.pdu, parsed));
+            return;
+        }
+    };
+    
+    let unit_id = adu.key.unit_id;
+    
+    // correlate with the request that produced this response.
+    // device identification has no addresses to correlate, but an identity is
+    // published retained, so an unrequested one would replace the real record
+    let request = if response.function() == FunctionCode::ReadDeviceIdentification {
+        if !TRANSACTIONS.with(|t| t.borrow_mut().complete_identification(adu.key)) {
+            MetricsTracker::record_unmatched();
+            return;
+        }
+        None
+    } else {
+        match TRANSACTIONS.with(|t| t.borrow_mut().complete(adu.key)) {
+            Correlation::Matched(request) => {
+                if let Err(e) = verify_response(&request, &response) {
+                    MetricsTracker::record_error(GatewayError::pdu(&e, framing, frame));
+                    return;
+                }
+                Some(request)
+            }
+            Correlation::Unmatched => {
+                MetricsTracker::record_unmatched();
+                None
+            }
+            Correlation::Duplicate => {
+                MetricsTracker::record_duplicate();
+                return;
+            }
+        }
+    };
+    
+    // without a request we only know offsets, not real addresses
+    let start = request.as_ref().map(|r| r.start_address);
+    let correlation = request.as_ref().map(|_| adu.key);
+    let register_map = &config::get().register_map;
+    
+    match response {
+        ResponsePdu::Read(response) => {
+            let mut registers = register_map.registers(unit_id, response.function, start, &response.registers);
+            let mut status_bits = register_map.status_bits(unit_id, response.function, start, &response.registers);
+            let mut changes = Vec::new();
+            let mut alarms = Vec::new();
+            let mut anomalies = Vec::new();
+            if let Some(table) = Table::for_function(response.function) {
+                // events, alarms and anomaly checks see every sample, before any is suppressed
+                let now_ms = elapsed_ms();
+                changes = STATUS_BITS.with(|d| d.borrow_mut().observe(unit_id, table, &status_bits));
+                alarms = ALARMS.with(|a| a.borrow_mut().evaluate(register_map, unit_id, table, &registers));
+                anomalies = ANOMALIES.with(|a| a.borrow_mut().evaluate(register_map, unit_id, table, &registers, now_ms));
+                MetricsTracker::record_anomalies(anomalies.len() as u64);
+                let suppressed = REPORTER.with(|r| {
+                    let mut reporter = r.borrow_mut();
+                    reporter.retain_registers(register_map, unit_id, table, &mut registers, now_ms)
+                        + reporter.retain_status_bits(register_map, unit_id, table, &mut status_bits, now_ms)
+                });
+                MetricsTracker::record_suppressed(suppressed);
+            }
+            let published = publish_telemetry(unit_id, response.function, correlation, TelemetryData::Registers(registers), status_bits, frame_size, meta);
+            settle_reports(published);
+            for change in changes {
+                publish_bit_change(unit_id, response.function, change, meta);
+            }
+            for output in alarms {
+                match output {
+                    AlarmOutput::Event(transition, alarm) => publish_alarm(unit_id, transition, alarm, meta),
+                    AlarmOutput::State(state) => publish_alarm_state(state, Some(meta)),
+                }
+            }
+            for anomaly in anomalies {
+                publish_anomaly(unit_id, anomaly, meta);
+            }
+        }
+        ResponsePdu::ReadBits(response) => {
+            // drop the zero padding in the last byte when the quantity is known -
+            // otherwise all bits go out and the payload says the tail may be padding
+            let count = request.as_ref().map_or(response.bits.len(), |r| r.quantity as usize);
+            let values = &response.bits[..count.min(response.bits.len())];
+            let mut bits = register_map.bits(unit_id, response.function, start, values);
+            if let Some(table) = Table::for_function(response.function) {
+                let suppressed = REPORTER.with(|r| {
+                    r.borrow_mut().retain_bits(register_map, unit_id, table, &mut bits, elapsed_ms())
+                });
+                MetricsTracker::record_suppressed(suppressed);
+            }
+            let published = publish_telemetry(unit_id, response.function, correlation, TelemetryData::Bits(bits), Vec::new(), frame_size, meta);
+            settle_reports(published);
+        }
+        ResponsePdu::Exception(exception) => publish_exception(unit_id, &exception, correlation, frame_size, meta),
+        ResponsePdu::DeviceId(response) => publish_device_identity(unit_id, response, framing, frame, meta),
+    }
+}
+
+/// the host's description of a received frame, bounded for use in topics
+fn frame_meta(meta: gateway::protocols::modbus_source::FrameMeta) -> Result<FrameMeta, String> {
+    let at = meta.received_at;
+    let received_ms = timestamp::epoch_ms(at.seconds, at.nanoseconds);
+    FrameMeta::new(meta.source_uri, meta.connection_id, received_ms)
+}
+
+/// milliseconds since the unix epoch from the host's wall clock,
+/// for timestamps and shelve expiry
+fn clock_ms() -> u64 {
+    let now = wasi::clocks::wall_clock::now();
+    timestamp::epoch_ms(now.seconds, now.nanoseconds)
+}
+
+/// milliseconds on the host's monotonic clock, for deadband silence and
+/// anomaly rates - wall clock steps would fake or hide a change
+fn elapsed_ms() -> u64 {
+    wasi::clocks::monotonic_clock::now() / 1_000_000
+}
+
+/// publish time of a payload
+fn publish_time() -> String {
+    timestamp::rfc3339(clock_ms())
+}
+
+/// how far into the pdu the parser got before failing
+fn parsed_len(pdu: &[u8], err: &nom::Err<nom::error::Error<&[u8]>>) -> usize {
+    match err {
+        nom::Err::Error(e) | nom::Err::Failure(e) => pdu.len().saturating_sub(e.input.len()),
+        nom::Err::Incomplete(_) => pdu.len(),
+    }
+}
+
+/// inclusive address range a validated request covers
+fn request_range(request: &ReadRequest) -> (u16, u16) {
+    let end = request.start_address as u32 + request.quantity.saturating_sub(1) as u32;
+    (request.start_address, end.min(u16::MAX as u32) as u16)
+}
+
+/// framing of whole request/reply frames - requests are always handed
+/// over whole, even when responses are streamed
+fn request_framing() -> Framing {
+    match source_mode() {
+        SourceMode::Framed(framing) => framing,
+        SourceMode::Stream => Framing::Tcp,
+    }
+}
+
+/// delivery mode and framing the host uses for this source
+fn source_mode() -> SourceMode {
+    use gateway::protocols::modbus_source::Framing as WitFraming;
+    match gateway::protocols::modbus_source::source_framing() {
+        WitFraming::Tcp => SourceMode::Framed(Framing::Tcp),
+        WitFraming::TcpStream => SourceMode::Stream,
+        WitFraming::Rtu => SourceMode::Framed(Framing::Rtu),
+        WitFraming::Ascii => SourceMode::Framed(Framing::Ascii),
+    }
+}
+
+/// build and publish a telemetry payload for a read response -
+/// false if a publish failed
+fn publish_telemetry(
+    unit_id: u8,
+    function: FunctionCode,
+    correlation: Option<TransactionKey>,
+    data: TelemetryData,
+    status_bits: Vec<StatusBit>,
+    frame_size: u64,
+    meta: &FrameMeta,
+) -> bool {
+    // the register map or report-by-exception left nothing to publish
+    if data.is_empty() && status_bits.is_empty() {
+        MetricsTracker::record_frame(frame_size);
+        return true;
+    }
+    
+    let config = config::get();
+    if let TelemetryFormat::SparkplugB(node) = &config.telemetry_format {
+        let table = Table::for_function(function);
+        let outputs = table.map_or_else(Vec::new, |table| {
+            SPARKPLUG.with(|s| {
+                let mut session = s.borrow_mut();
+                session.data(node, &config.register_map, unit_id, table, &data, &status_bits, meta.received_ms, clock_ms())
+            })
+        });
+        let published = publish_sparkplug(outputs);
+        if published {
+            MetricsTracker::record_frame(frame_size);
+        }
+        return published;
+    }
+    
+    // one message per response, or per point when the topic names the tag
+    let messages = if config.topics.uses_tag(Kind::Telemetry) {
+        split_by_tag(unit_id, function, data, status_bits)
+    } else {
+        vec![(String::new(), data, status_bits)]
+    };
+    
+    for (tag, data, status_bits) in messages {
         // build mqtt payload
         let payload = TelemetryPayload {
-            source: "modbus://plc:502".to_string(),
-            unit_id: header.unit_id,
-            function: match response.function {
-                FunctionCode::ReadHoldingRegisters => "read_holding_registers".to_string(),
-                FunctionCode::ReadInputRegisters => "read_input_registers".to_string(),
-            },
-            registers: response.registers.iter().enumerate().map(|(i, &value)| {
-                Register {
-                    address: i as u16,
-                    value,
-                    label: None,
-                }
-            }).collect(),
-            timestamp: "2026-01-05T00:00:00Z".to_string(),
+            source: meta.source.clone(),
+            unit_id,
+            function: function.name().to_string(),
+            correlated: correlation.is_some(),
+            may_be_padded: correlation.is_none() && matches!(data, TelemetryData::Bits(_)),
+            data,
+            status_bits,
+            received_at: meta.received_at(),
+            timestamp: publish_time(),
         };
         
         let json = payload.to_json();
         let json_size = json.len() as u64;
         
         // publish
-        let topic = format!("ics/telemetry/unit_{}", header.unit_id);
-        if let Err(e) = gateway::protocols::mqtt_sink::publish(&topic, &json, 0) {
-            record_error(format!("mqtt publish error: {}", e.message));
+        let values = TopicValues { unit_id, function: function.name(), tag: &tag, source: &meta.source, connection_id: &meta.connection_id, ..Default::default() };
+        let topic = config.topics.render(Kind::Telemetry, &values);
+        let options = PublishOptions {
+            message_expiry_secs: config.publish.telemetry_expiry_secs,
+            correlation_data: correlation.map(correlation_data),
+            ..json_options(0, false)
+        };
+        if let Err(e) = mqtt_sink::publish(&topic, &json, &options) {
+            MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+            return false;
+        }
+        MetricsTracker::record_outbound(json_size);
+    }
+    
+    MetricsTracker::record_frame(frame_size);
+    true
+}
+
+/// keep the values report-by-exception admitted only if they reached the
+/// broker - a failed publish must not leave them suppressed as already sent
+fn settle_reports(published: bool) {
+    REPORTER.with(|r| {
+        let mut reporter = r.borrow_mut();
+        if published {
+            reporter.commit();
+        } else {
+            reporter.discard();
+        }
+    });
+}
+
+/// split telemetry into one message per point, named by its tag - or by
+/// its address when untagged. status bits travel with their word.
+fn split_by_tag(
+    unit_id: u8,
+    function: FunctionCode,
+    data: TelemetryData,
+    status_bits: Vec<StatusBit>,
+) -> Vec<(String, TelemetryData, Vec<StatusBit>)> {
+    let mut points = BTreeMap::new();
+    match data {
+        TelemetryData::Registers(registers) => {
+            for register in registers {
+                let name = register.label.clone().unwrap_or_else(|| register.address.to_string());
+                points.insert(register.address, (name, TelemetryData::Registers(vec![register]), Vec::new()));
+            }
+        }
+        TelemetryData::Bits(bits) => {
+            for bit in bits {
+                let name = bit.label.clone().unwrap_or_else(|| bit.address.to_string());
+                points.insert(bit.address, (name, TelemetryData::Bits(vec![bit]), Vec::new()));
+            }
+        }
+    }
+    // a word that publishes only its bits has no register of its own
+    for bit in status_bits {
+        let address = bit.address;
+        let point = points.entry(address).or_insert_with(|| {
+            let name = word_tag(unit_id, Table::for_function(function), address);
+            (name, TelemetryData::Registers(Vec::new()), Vec::new())
+        });
+        point.2.push(bit);
+    }
+    points.into_values().collect()
+}
+
+/// tag name of the status word at `address` - status bits only come from
+/// correlated responses, so the address is real
+fn word_tag(unit_id: u8, table: Option<Table>, address: u16) -> String {
+    let register_map = &config::get().register_map;
+    table
+        .and_then(|table| register_map.get(&PointKey { unit_id, table, address }))
+        .map_or_else(|| address.to_string(), |tag| tag.name.clone())
+}
+
+/// register sparkplug death certificates as the will and publish the rest,
+/// in order. stops at the first failure, since a birth that never arrived
+/// makes what follows meaningless - the host application will ask for a
+/// rebirth. returns whether everything went out.
+fn publish_sparkplug(outputs: Vec<sparkplug::Output>) -> bool {
+    // sparkplug carries its own schema in the topic namespace
+    let options = |qos| PublishOptions {
+        qos,
+        retain: false,
+        message_expiry_secs: None,
+        content_type: Some("application/x-protobuf".to_string()),
+        correlation_data: None,
+        user_properties: user_properties(None),
+    };
+    for output in outputs {
+        match output {
+            sparkplug::Output::Will { topic, payload } => {
+                if let Err(e) = mqtt_sink::set_will(&topic, &payload, &options(1)) {
+                    MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+                    return false;
+                }
+            }
+            sparkplug::Output::Publish { topic, payload } => {
+                if let Err(e) = mqtt_sink::publish_binary(&topic, &payload, &options(0)) {
+                    MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+                    return false;
+                }
+                MetricsTracker::record_outbound(payload.len() as u64);
+            }
+        }
+    }
+    true
+}
+
+/// publish options for a json document. the content type, gateway id and
+/// schema version let consumers route and validate without parsing.
+fn json_options(qos: u8, retain: bool) -> PublishOptions {
+    PublishOptions {
+        qos,
+        retain,
+        message_expiry_secs: None,
+        content_type: Some("application/json".to_string()),
+        correlation_data: None,
+        user_properties: user_properties(Some(payload::SCHEMA_VERSION)),
+    }
+}
+
+/// the gateway id, if configured, and the payload schema version
+fn user_properties(schema_version: Option<&str>) -> Vec<(String, String)> {
+    let gateway_id = &config::get().publish.gateway_id;
+    let mut properties = Vec::new();
+    if !gateway_id.is_empty() {
+        properties.push(("gateway_id".to_string(), gateway_id.clone()));
+    }
+    if let Some(version) = schema_version {
+        properties.push(("schema_version".to_string(), version.to_string()));
+    }
+    properties
+}
+
+/// correlation data for a response: the transaction id, big-endian,
+/// then the unit id - the request's transaction key as sent on the wire
+fn correlation_data(key: TransactionKey) -> Vec<u8> {
+    let [high, low] = key.transaction_id.to_be_bytes();
+    vec![high, low, key.unit_id]
+}
+
+/// publish one flipped status bit to the events topic.
+/// the telemetry already counted the frame, so only outbound bytes are added.
+fn publish_bit_change(unit_id: u8, function: FunctionCode, bit: StatusBit, meta: &FrameMeta) {
+    let tag = word_tag(unit_id, Table::for_function(function), bit.address);
+    let payload = BitChangePayload {
+        source: meta.source.clone(),
+        unit_id,
+        event: "bit_change",
+        bit,
+        received_at: meta.received_at(),
+        timestamp: publish_time(),
+    };
+    
+    let json = payload.to_json();
+    let json_size = json.len() as u64;
+    
+    let values = TopicValues { unit_id, tag: &tag, source: &meta.source, connection_id: &meta.connection_id, ..Default::default() };
+    let topic = config::get().topics.render(Kind::Events, &values);
+    if let Err(e) = mqtt_sink::publish(&topic, &json, &json_options(1, false)) {
+        MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+        return;
+    }
+    
+    MetricsTracker::record_outbound(json_size);
+}
+
+/// publish a raised or cleared limit alarm to the alarms topic
+fn publish_alarm(unit_id: u8, transition: Transition, alarm: Alarm, meta: &FrameMeta) {
+    let values = TopicValues { unit_id, tag: &alarm.tag, level: alarm.level, source: &meta.source, connection_id: &meta.connection_id, ..Default::default() };
+    let topic = config::get().topics.render(Kind::Alarms, &values);
+    let payload = AlarmPayload {
+        source: meta.source.clone(),
+        unit_id,
+        event: transition.event(),
+        alarm,
+        received_at: meta.received_at(),
+        timestamp: publish_time(),
+    };
+    
+    let json = payload.to_json();
+    let json_size = json.len() as u64;
+    
+    if let Err(e) = mqtt_sink::publish(&topic, &json, &json_options(1, false)) {
+        MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+        return;
+    }
+    
+    MetricsTracker::record_outbound(json_size);
+}
+
+/// publish a failed anomaly check to the security topic
+fn publish_anomaly(unit_id: u8, anomaly: Anomaly, meta: &FrameMeta) {
+    let values = TopicValues { unit_id, tag: &anomaly.tag, source: &meta.source, connection_id: &meta.connection_id, ..Default::default() };
+    let topic = config::get().topics.render(Kind::Security, &values);
+    let payload = AnomalyPayload {
+        source: meta.source.clone(),
+        unit_id,
+        event: "security_anomaly",
+        anomaly,
+        received_at: meta.received_at(),
+        timestamp: publish_time(),
+    };
+    
+    let json = payload.to_json();
+    let json_size = json.len() as u64;
+    
+    if let Err(e) = mqtt_sink::publish(&topic, &json, &json_options(1, false)) {
+        MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+        return;
+    }
+    
+    MetricsTracker::record_outbound(json_size);
+}
+
+/// publish an alarm's current state, retained, to its own topic.
+/// states changed by operator commands have no frame to name a source.
+fn publish_alarm_state(alarm: AlarmState, meta: Option<&FrameMeta>) {
+    let values = TopicValues {
+        unit_id: alarm.unit_id,
+        tag: &alarm.tag,
+        level: alarm.level,
+        ..Default::default()
+    };
+    let topic = config::get().topics.render(Kind::AlarmState, &values);
+    let payload = AlarmStatePayload {
+        source: meta.map(|m| m.source.clone()),
+        alarm,
+        timestamp: publish_time(),
+    };
+    
+    let json = payload.to_json();
+    let json_size = json.len() as u64;
+    
+    if let Err(e) = mqtt_sink::publish(&topic, &json, &json_options(1, true)) {
+        MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+        return;
+    }
+    
+    MetricsTracker::record_outbound(json_size);
+}
+
+/// publish an exception response to the status topic
+/// counted as a processed frame plus an exception, never as invalid
+fn publish_exception(
+    unit_id: u8,
+    exception: &ExceptionResponse,
+    correlation: Option<TransactionKey>,
+    frame_size: u64,
+    meta: &FrameMeta,
+) {
+    let payload = ExceptionPayload {
+        source: meta.source.clone(),
+        unit_id,
+        function: exception.function.name().to_string(),
+        exception_code: exception.code.to_byte(),
+        exception: exception.code.name().to_string(),
+        received_at: meta.received_at(),
+        timestamp: publish_time(),
+    };
+    
+    let json = payload.to_json();
+    let json_size = json.len() as u64;
+    
+    let values = TopicValues { unit_id, function: exception.function.name(), source: &meta.source, connection_id: &meta.connection_id, ..Default::default() };
+    let topic = config::get().topics.render(Kind::Status, &values);
+    let options = PublishOptions {
+        correlation_data: correlation.map(correlation_data),
+        ..json_options(1, false)
+    };
+    if let Err(e) = mqtt_sink::publish(&topic, &json, &options) {
+        MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+        return;
+    }
+    
+    MetricsTracker::record_exception(exception.code.to_byte());
+    MetricsTracker::record_frame(frame_size);
+    MetricsTracker::record_outbound(json_size);
+}
+
+/// merge a device identification response and, once the device reports
+/// nothing more follows, publish its identity as a retained document
+fn publish_device_identity(unit_id: u8, response: DeviceIdResponse, framing: Framing, frame: &[u8], meta: &FrameMeta) {
+    let frame_size = frame.len() as u64;
+    let identity = match INVENTORY.with(|i| i.borrow_mut().apply(unit_id, response)) {
+        Ok(Some(identity)) => identity,
+        Ok(None) => {
+            // continuation pending - the frame itself was fine
+            MetricsTracker::record_frame(frame_size);
             return;
         }
-        
-        record_frame(frame_size);
-        record_outbound(json_size);
+        Err(msg) => {
+            let err = GatewayError::in_pdu(GatewayError::BadLength, framing, frame, 0, msg.to_string());
+            MetricsTracker::record_error(err);
+            return;
+        }
+    };
+    
+    let payload = DeviceIdentityPayload {
+        source: meta.source.clone(),
+        unit_id,
+        conformity_level: identity.conformity_level,
+        vendor_name: identity.text(inventory::VENDOR_NAME),
+        product_code: identity.text(inventory::PRODUCT_CODE),
+        revision: identity.text(inventory::MAJOR_MINOR_REVISION),
+        vendor_url: identity.text(inventory::VENDOR_URL),
+        product_name: identity.text(inventory::PRODUCT_NAME),
+        model_name: identity.text(inventory::MODEL_NAME),
+        user_application_name: identity.text(inventory::USER_APPLICATION_NAME),
+        extended: identity.objects.keys()
+            .filter(|&&id| id >= inventory::FIRST_EXTENDED_OBJECT)
+            .filter_map(|&id| identity.text(id).map(|text| (format!("0x{:02X}", id), text)))
+            .collect(),
+        received_at: meta.received_at(),
+        timestamp: publish_time(),
+    };
+    
+    let json = payload.to_json();
+    let json_size = json.len() as u64;
+    
+    let values = TopicValues { unit_id, source: &meta.source, connection_id: &meta.connection_id, ..Default::default() };
+    let topic = config::get().topics.render(Kind::Devices, &values);
+    if let Err(e) = mqtt_sink::publish(&topic, &json, &json_options(1, true)) {
+        MetricsTracker::record_error(GatewayError::PublishFailure(e.message));
+        return;
     }
+    
+    MetricsTracker::record_frame(frame_size);
+    MetricsTracker::record_outbound(json_size);
 }
 
 impl exports::gateway::protocols::metrics::Guest for Component {
     fn get_stats() -> exports::gateway::protocols::metrics::GatewayStats {
-        exports::gateway::protocols::metrics::GatewayStats {
-            frames_processed: FRAMES_PROCESSED.with(|f| f.get()),
-            frames_invalid: FRAMES_INVALID.with(|f| f.get()),
-            bytes_in: BYTES_IN.with(|b| b.get()),
-            bytes_out: BYTES_OUT.with(|b| b.get()),
-            last_error: LAST_ERROR.with(|e| e.borrow().clone()),
-        }
+        // a host that checks stats before sending frames still sees config errors
+        config::load();
+        MetricsTracker::get_snapshot()
+    }
+}
+
+impl exports::gateway::protocols::codec::Guest for Component {
+    fn encode_read_request(transaction_id: u16, unit_id: u8, function: u8, start_address: u16, quantity: u16) -> Result<Vec<u8>, String> {
+        let function = FunctionCode::from_byte(function)
+            .filter(|f| f.is_bit_access() || f.is_register_access())
+            .ok_or_else(|| format!("function 0x{:02X} is not a data read", function))?;
+        let request = ReadRequest {
+            function,
+            start_address,
+            quantity,
+        };
+        request.validate().map_err(|e| e.to_string())?;
+        
+        let pdu = request.to_bytes();
+        let mut frame = MbapHeader::new(transaction_id, unit_id, pdu.len()).map_err(|e| e.to_string())?.to_bytes();
+        frame.extend_from_slice(&pdu);
+        Ok(frame)
+    }
+    
+    fn encode_read_response(transaction_id: u16, unit_id: u8, function: u8, registers: Vec<u16>) -> Result<Vec<u8>, String> {
+        let function = FunctionCode::from_byte(function)
+            .filter(|f| f.is_register_access())
+            .ok_or_else(|| format!("function 0x{:02X} is not a register read", function))?;
+        let pdu = ReadResponse::new(function, registers).map_err(|e| e.to_string())?.to_bytes();
+        let mut frame = MbapHeader::new(transaction_id, unit_id, pdu.len()).map_err(|e| e.to_string())?.to_bytes();
+        frame.extend_from_slice(&pdu);
+        Ok(frame)
     }
 }
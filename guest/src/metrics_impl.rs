@@ -5,15 +5,32 @@
 // renamed from metrics.rs to avoid collision with wit-generated metrics module.
 
 use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+
+use crate::error::GatewayError;
+use crate::exports::gateway::protocols::metrics::{self as wit, ExceptionCount, GatewayStats};
 
 // module-level storage for metrics
 // wasm component model instances are single-threaded, so we use Cell instead of atomics
 thread_local! {
-    static FRAMES_PROCESSED: Cell<u64> = Cell::new(0);
-    static FRAMES_INVALID: Cell<u64> = Cell::new(0);
-    static BYTES_IN: Cell<u64> = Cell::new(0);
-    static BYTES_OUT: Cell<u64> = Cell::new(0);
-    static LAST_ERROR: RefCell<Option<String>> = RefCell::new(None);
+    static FRAMES_PROCESSED: Cell<u64> = const { Cell::new(0) };
+    static FRAMES_INVALID: Cell<u64> = const { Cell::new(0) };
+    static BYTES_IN: Cell<u64> = const { Cell::new(0) };
+    static BYTES_OUT: Cell<u64> = const { Cell::new(0) };
+    static LAST_ERROR: RefCell<Option<GatewayError>> = const { RefCell::new(None) };
+    static EXCEPTIONS_RECEIVED: Cell<u64> = const { Cell::new(0) };
+    static EXCEPTION_COUNTS: RefCell<BTreeMap<u8, u64>> = const { RefCell::new(BTreeMap::new()) };
+    static RESPONSES_UNMATCHED: Cell<u64> = const { Cell::new(0) };
+    static RESPONSES_DUPLICATE: Cell<u64> = const { Cell::new(0) };
+    static STREAM_BYTES_DISCARDED: Cell<u64> = const { Cell::new(0) };
+    static FRAMES_DENIED: Cell<u64> = const { Cell::new(0) };
+    static LAST_DENIAL: RefCell<Option<String>> = const { RefCell::new(None) };
+    static POLLS_SENT: Cell<u64> = const { Cell::new(0) };
+    static POLL_FAILURES: Cell<u64> = const { Cell::new(0) };
+    static UPDATES_SUPPRESSED: Cell<u64> = const { Cell::new(0) };
+    static COMMANDS_APPLIED: Cell<u64> = const { Cell::new(0) };
+    static COMMANDS_REJECTED: Cell<u64> = const { Cell::new(0) };
+    static SECURITY_ANOMALIES: Cell<u64> = const { Cell::new(0) };
 }
 
 /// metrics tracking for the gateway
@@ -30,9 +47,83 @@ impl MetricsTracker {
 
     /// record a parse or publish error
     /// called when frame is malformed or mqtt publish fails
-    pub fn record_error(msg: String) {
+    pub fn record_error(err: GatewayError) {
         FRAMES_INVALID.with(|f| f.set(f.get() + 1));
-        LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+        LAST_ERROR.with(|e| *e.borrow_mut() = Some(err));
+    }
+
+    /// record a well-formed exception response from a slave
+    /// kept apart from frames_invalid - an exception is a plc-side fault, not an attack
+    pub fn record_exception(code: u8) {
+        EXCEPTIONS_RECEIVED.with(|e| e.set(e.get() + 1));
+        EXCEPTION_COUNTS.with(|c| *c.borrow_mut().entry(code).or_insert(0) += 1);
+    }
+
+    /// record a response with no matching in-flight request
+    /// it is still published, but without real register addresses
+    pub fn record_unmatched() {
+        RESPONSES_UNMATCHED.with(|r| r.set(r.get() + 1));
+    }
+
+    /// record a response for a transaction that was already answered
+    /// duplicates are dropped - a replayed response must not be republished
+    pub fn record_duplicate() {
+        RESPONSES_DUPLICATE.with(|r| r.set(r.get() + 1));
+    }
+
+    /// record bytes skipped while resynchronizing a tcp byte stream
+    /// counted as one invalid frame per resync, since the bytes were never a frame
+    pub fn record_discarded(count: u64) {
+        STREAM_BYTES_DISCARDED.with(|s| s.set(s.get() + count));
+        Self::record_error(GatewayError::StreamResync(count));
+    }
+
+    /// record a well-formed frame rejected by the site policy
+    /// kept apart from frames_invalid - the frame was fine, the site forbids it
+    pub fn record_denied(msg: String) {
+        FRAMES_DENIED.with(|f| f.set(f.get() + 1));
+        LAST_DENIAL.with(|d| *d.borrow_mut() = Some(msg));
+    }
+    
+    /// record a scan-list request sent through modbus-client
+    pub fn record_poll() {
+        POLLS_SENT.with(|p| p.set(p.get() + 1));
+    }
+
+    /// record a poll that got no reply (timeout or transport error)
+    /// no frame arrived, so frames_invalid is left alone
+    pub fn record_poll_failure(msg: String) {
+        POLL_FAILURES.with(|p| p.set(p.get() + 1));
+        LAST_ERROR.with(|e| *e.borrow_mut() = Some(GatewayError::PollFailure(msg)));
+    }
+    
+    /// record tagged points left out of a publish by their deadband
+    pub fn record_suppressed(count: u64) {
+        UPDATES_SUPPRESSED.with(|u| u.set(u.get() + count));
+    }
+    
+    /// record an operator command applied to an alarm
+    pub fn record_command() {
+        COMMANDS_APPLIED.with(|c| c.set(c.get() + 1));
+    }
+
+    /// record an operator command naming no configured alarm
+    /// commands are not frames, so frames_invalid is left alone
+    pub fn record_command_rejected(msg: String) {
+        COMMANDS_REJECTED.with(|c| c.set(c.get() + 1));
+        LAST_ERROR.with(|e| *e.borrow_mut() = Some(GatewayError::InvalidCommand(msg)));
+    }
+    
+    /// record a configuration entry that was rejected or replaced
+    /// config loads on the first frame, but is not one - frames_invalid is left alone
+    pub fn record_config_error(msg: String) {
+        LAST_ERROR.with(|e| *e.borrow_mut() = Some(GatewayError::InvalidConfig(msg)));
+    }
+    
+    /// record tagged values that failed an anomaly check
+    /// kept apart from frames_invalid - the frame was well formed, its values were not
+    pub fn record_anomalies(count: u64) {
+        SECURITY_ANOMALIES.with(|s| s.set(s.get() + count));
     }
     
     /// record outbound mqtt payload size
@@ -44,13 +135,59 @@ impl MetricsTracker {
     /// get current stats snapshot
     /// connects to the wit export 'metrics::get-stats'
     /// the host calls this to display live stats on the dashboard
-    pub fn get_snapshot() -> crate::exports::gateway::protocols::metrics::GatewayStats {
-        crate::exports::gateway::protocols::metrics::GatewayStats {
+    pub fn get_snapshot() -> GatewayStats {
+        GatewayStats {
             frames_processed: FRAMES_PROCESSED.with(|f| f.get()),
             frames_invalid: FRAMES_INVALID.with(|f| f.get()),
             bytes_in: BYTES_IN.with(|b| b.get()),
             bytes_out: BYTES_OUT.with(|b| b.get()),
-            last_error: LAST_ERROR.with(|e| e.borrow().clone()),
+            last_error: LAST_ERROR.with(|e| e.borrow().as_ref().map(error_to_wit)),
+            exceptions_received: EXCEPTIONS_RECEIVED.with(|e| e.get()),
+            exception_counts: EXCEPTION_COUNTS.with(|c| {
+                c.borrow()
+                    .iter()
+                    .map(|(&code, &count)| ExceptionCount { code, count })
+                    .collect()
+            }),
+            responses_unmatched: RESPONSES_UNMATCHED.with(|r| r.get()),
+            responses_duplicate: RESPONSES_DUPLICATE.with(|r| r.get()),
+            stream_bytes_discarded: STREAM_BYTES_DISCARDED.with(|s| s.get()),
+            frames_denied: FRAMES_DENIED.with(|f| f.get()),
+            last_denial: LAST_DENIAL.with(|d| d.borrow().clone()),
+            polls_sent: POLLS_SENT.with(|p| p.get()),
+            poll_failures: POLL_FAILURES.with(|p| p.get()),
+            updates_suppressed: UPDATES_SUPPRESSED.with(|u| u.get()),
+            commands_applied: COMMANDS_APPLIED.with(|c| c.get()),
+            commands_rejected: COMMANDS_REJECTED.with(|c| c.get()),
+            security_anomalies: SECURITY_ANOMALIES.with(|s| s.get()),
         }
     }
 }
+
+/// map the guest error onto the wit variant of the same shape
+fn error_to_wit(err: &GatewayError) -> wit::GatewayError {
+    use wit::GatewayError as W;
+    let fault = |f: &crate::error::FrameFault| wit::FrameFault {
+        offset: f.offset as u32,
+        prefix: f.prefix.clone(),
+        detail: f.detail.clone(),
+    };
+    match err {
+        GatewayError::TruncatedHeader(f) => W::TruncatedHeader(fault(f)),
+        GatewayError::BadProtocolId(f) => W::BadProtocolId(fault(f)),
+        GatewayError::BadLength(f) => W::BadLength(fault(f)),
+        GatewayError::BadChecksum(f) => W::BadChecksum(fault(f)),
+        GatewayError::BadFraming(f) => W::BadFraming(fault(f)),
+        GatewayError::IllegalFunction(f) => W::IllegalFunction(fault(f)),
+        GatewayError::ByteCountMismatch(f) => W::ByteCountMismatch(fault(f)),
+        GatewayError::MalformedPdu(f) => W::MalformedPdu(fault(f)),
+        GatewayError::InvalidRequest(f) => W::InvalidRequest(fault(f)),
+        GatewayError::ResponseMismatch(f) => W::ResponseMismatch(fault(f)),
+        GatewayError::StreamResync(count) => W::StreamResync(*count),
+        GatewayError::ReceiveFailure(msg) => W::ReceiveFailure(msg.clone()),
+        GatewayError::PublishFailure(msg) => W::PublishFailure(msg.clone()),
+        GatewayError::PollFailure(msg) => W::PollFailure(msg.clone()),
+        GatewayError::InvalidConfig(msg) => W::InvalidConfig(msg.clone()),
+        GatewayError::InvalidCommand(msg) => W::InvalidCommand(msg.clone()),
+    }
+}
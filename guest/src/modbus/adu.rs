@@ -0,0 +1,122 @@
+// guest/src/modbus/adu.rs
+// strips the transport framing (tcp mbap header, rtu address + crc, or ascii
+// hex + lrc) from a raw frame and hands back the pdu with the key used for
+// correlation.
+// everything after this point is framing-independent.
+
+use std::borrow::Cow;
+
+use super::ascii::{self, AsciiFrame};
+use super::frame::MbapHeader;
+use super::rtu::{self, RtuFrame};
+use super::transaction::TransactionKey;
+use super::validate::{validate_mbap, FrameError};
+
+/// wire framing used by a modbus source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Framing {
+    Tcp,  // mbap header, one frame per tcp message
+    Rtu,  // serial line, address + pdu + crc16
+    Ascii, // serial line, ':' + hex(address + pdu + lrc) + crlf
+}
+
+/// a framing-verified application data unit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adu<'a> {
+    pub key: TransactionKey,  // serial framings have no transaction id, so it is always 0
+    pub pdu: Cow<'a, [u8]>,   // borrowed for binary framings, owned once ascii is hex-decoded
+}
+
+/// decode the framing of one frame.
+/// only the transport layer is checked here - pdu contents are validated
+/// by the caller, since requests and responses have different layouts.
+pub fn decode(framing: Framing, frame: &[u8]) -> Result<Adu<'_>, FrameError> {
+    match framing {
+        Framing::Tcp => {
+            let (pdu, header) = MbapHeader::parse(frame).map_err(|_| FrameError::TruncatedHeader)?;
+            validate_mbap(&header, pdu)?;
+            Ok(Adu {
+                key: TransactionKey {
+                    transaction_id: header.transaction_id,
+                    unit_id: header.unit_id,
+                },
+                pdu: Cow::Borrowed(pdu),
+            })
+        }
+        Framing::Rtu => {
+            let frame = RtuFrame::parse(frame)?;
+            Ok(Adu {
+                key: TransactionKey {
+                    transaction_id: 0,
+                    unit_id: frame.address,
+                },
+                pdu: Cow::Borrowed(frame.pdu),
+            })
+        }
+        Framing::Ascii => {
+            let frame = AsciiFrame::parse(frame)?;
+            Ok(Adu {
+                key: TransactionKey {
+                    transaction_id: 0,
+                    unit_id: frame.address,
+                },
+                pdu: Cow::Owned(frame.pdu),
+            })
+        }
+    }
+}
+
+/// wrap a pdu in the given framing - the inverse of decode.
+/// serial framings carry no transaction id, so only the unit id is used.
+pub fn encode(framing: Framing, key: TransactionKey, pdu: &[u8]) -> Result<Vec<u8>, FrameError> {
+    match framing {
+        Framing::Tcp => {
+            let mut frame = MbapHeader::new(key.transaction_id, key.unit_id, pdu.len())?.to_bytes();
+            frame.extend_from_slice(pdu);
+            Ok(frame)
+        }
+        Framing::Rtu => Ok(rtu::encode(key.unit_id, pdu)),
+        Framing::Ascii => Ok(ascii::encode(key.unit_id, pdu)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_all_framings_to_same_pdu() {
+        let tcp = [0x00, 0x07, 0x00, 0x00, 0x00, 0x05, 0x02, 0x03, 0x02, 0x00, 0x2A];
+        let rtu = [0x02, 0x03, 0x02, 0x00, 0x2A, 0x7D, 0x9B];
+        let ascii = b":020302002ACF\r\n";
+
+        let tcp_adu = decode(Framing::Tcp, &tcp).unwrap();
+        let rtu_adu = decode(Framing::Rtu, &rtu).unwrap();
+        let ascii_adu = decode(Framing::Ascii, ascii).unwrap();
+
+        assert_eq!(tcp_adu.pdu, rtu_adu.pdu);
+        assert_eq!(tcp_adu.pdu, ascii_adu.pdu);
+        assert_eq!(tcp_adu.key, TransactionKey { transaction_id: 7, unit_id: 2 });
+        assert_eq!(rtu_adu.key, TransactionKey { transaction_id: 0, unit_id: 2 });
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let pdu = [0x04, 0x00, 0x10, 0x00, 0x02];
+        for framing in [Framing::Tcp, Framing::Rtu, Framing::Ascii] {
+            let key = TransactionKey {
+                transaction_id: if framing == Framing::Tcp { 0x0102 } else { 0 },
+                unit_id: 9,
+            };
+            let frame = encode(framing, key, &pdu).unwrap();
+            let adu = decode(framing, &frame).unwrap();
+            assert_eq!(adu.key, key);
+            assert_eq!(&adu.pdu[..], &pdu[..]);
+        }
+    }
+
+    #[test]
+    fn test_decode_truncated_tcp_header() {
+        assert_eq!(decode(Framing::Tcp, &[0x00, 0x01, 0x00]), Err(FrameError::TruncatedHeader));
+    }
+}
@@ -0,0 +1,233 @@
+// guest/src/modbus/ascii.rs
+// decodes modbus ascii frames used by older serial devices.
+// ascii layout: ':' [address][pdu][lrc] as uppercase hex pairs, then "\r\n".
+// every character is validated before decoding - a single non-hex byte or an
+// odd number of hex digits rejects the whole frame, never a partial decode.
+
+use super::validate::FrameError;
+
+/// smallest ascii frame: ':' + address + function + lrc (2 chars each) + crlf
+pub const MIN_FRAME_LEN: usize = 9;
+
+/// largest ascii frame allowed by the serial line spec
+pub const MAX_FRAME_LEN: usize = 513;
+
+/// a decoded, lrc-verified ascii frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsciiFrame {
+    pub address: u8,   // slave address, 1-247 for unicast replies
+    pub pdu: Vec<u8>,  // function code + data, lrc stripped
+}
+
+impl AsciiFrame {
+    /// decode and verify one ascii frame.
+    /// checks delimiters, hex characters, lrc and slave address in that order.
+    pub fn parse(input: &[u8]) -> Result<Self, FrameError> {
+        if input.len() > MAX_FRAME_LEN {
+            return Err(FrameError::FrameTooLong(input.len()));
+        }
+        if input.first() != Some(&b':') {
+            return Err(FrameError::MissingStartDelimiter);
+        }
+        if !input.ends_with(b"\r\n") {
+            return Err(FrameError::MissingEndDelimiter);
+        }
+        if input.len() < MIN_FRAME_LEN {
+            return Err(FrameError::TruncatedHeader);
+        }
+
+        let hex = &input[1..input.len() - 2];
+        let bytes = decode_hex(hex)?;
+
+        // bytes holds at least address + function + lrc after the length checks
+        let (body, lrc_byte) = bytes.split_at(bytes.len() - 1);
+        let computed = lrc(body);
+        if computed != lrc_byte[0] {
+            return Err(FrameError::LrcMismatch { computed, received: lrc_byte[0] });
+        }
+
+        let address = body[0];
+        if address == 0 || address > 247 {
+            return Err(FrameError::InvalidSlaveAddress(address));
+        }
+
+        Ok(Self {
+            address,
+            pdu: body[1..].to_vec(),
+        })
+    }
+}
+
+/// build an ascii frame: ':' + uppercase hex(address + pdu + lrc) + crlf
+pub fn encode(address: u8, pdu: &[u8]) -> Vec<u8> {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+    let mut body = Vec::with_capacity(pdu.len() + 2);
+    body.push(address);
+    body.extend_from_slice(pdu);
+    body.push(lrc(&body));
+
+    let mut out = Vec::with_capacity(body.len() * 2 + 3);
+    out.push(b':');
+    for byte in body {
+        out.push(DIGITS[(byte >> 4) as usize]);
+        out.push(DIGITS[(byte & 0x0F) as usize]);
+    }
+    out.extend_from_slice(b"\r\n");
+    out
+}
+
+/// modbus longitudinal redundancy check: two's complement of the byte sum
+pub fn lrc(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)).wrapping_neg()
+}
+
+/// decode uppercase hex pairs. the spec only allows '0'-'9' and 'A'-'F'.
+fn decode_hex(hex: &[u8]) -> Result<Vec<u8>, FrameError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(FrameError::OddHexLength(hex.len()));
+    }
+
+    hex.chunks_exact(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            let hi = hex_value(pair[0]).ok_or(FrameError::InvalidHexCharacter {
+                position: 1 + i * 2,
+                byte: pair[0],
+            })?;
+            let lo = hex_value(pair[1]).ok_or(FrameError::InvalidHexCharacter {
+                position: 2 + i * 2,
+                byte: pair[1],
+            })?;
+            Ok((hi << 4) | lo)
+        })
+        .collect()
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::test_util::next;
+    use crate::modbus::function::{FunctionCode, ReadRequest, ResponsePdu};
+
+    #[test]
+    fn test_lrc_known_vector() {
+        // slave 0x11 reads 3 registers at 0x006B - lrc 0x7E
+        assert_eq!(lrc(&[0x11, 0x03, 0x00, 0x6B, 0x00, 0x03]), 0x7E);
+    }
+
+    #[test]
+    fn test_encode_matches_captured_request() {
+        let frame = encode(0x11, &[0x03, 0x00, 0x6B, 0x00, 0x03]);
+        assert_eq!(frame, b":1103006B00037E\r\n".to_vec());
+        assert_eq!(AsciiFrame::parse(&frame).unwrap().pdu, vec![0x03, 0x00, 0x6B, 0x00, 0x03]);
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let frame = AsciiFrame::parse(b":1103006B00037E\r\n").unwrap();
+
+        assert_eq!(frame.address, 0x11);
+        let (_, request) = ReadRequest::parse(&frame.pdu).unwrap();
+        assert_eq!(request, ReadRequest {
+            function: FunctionCode::ReadHoldingRegisters,
+            start_address: 0x6B,
+            quantity: 3,
+        });
+    }
+
+    #[test]
+    fn test_parse_response_feeds_read_response() {
+        // slave 1, 0x04, byte_count 2, register 0x002A
+        let body = [0x01, 0x04, 0x02, 0x00, 0x2A];
+        let mut data = b":010402002A".to_vec();
+        data.extend_from_slice(format!("{:02X}\r\n", lrc(&body)).as_bytes());
+
+        let frame = AsciiFrame::parse(&data).unwrap();
+        let (_, pdu) = ResponsePdu::parse(&frame.pdu).unwrap();
+        let ResponsePdu::Read(response) = pdu else { panic!("expected read response") };
+        assert_eq!(response.registers, vec![42]);
+    }
+
+    #[test]
+    fn test_reject_delimiters_and_lrc() {
+        assert_eq!(AsciiFrame::parse(b"1103006B00037E\r\n"), Err(FrameError::MissingStartDelimiter));
+        assert_eq!(AsciiFrame::parse(b":1103006B00037E"), Err(FrameError::MissingEndDelimiter));
+        assert_eq!(AsciiFrame::parse(b":1103\r\n"), Err(FrameError::TruncatedHeader));
+        assert_eq!(
+            AsciiFrame::parse(b":1103006B00037F\r\n"),
+            Err(FrameError::LrcMismatch { computed: 0x7E, received: 0x7F })
+        );
+    }
+
+    #[test]
+    fn test_reject_odd_length_and_non_hex() {
+        assert_eq!(AsciiFrame::parse(b":1103006B0003E\r\n"), Err(FrameError::OddHexLength(13)));
+        // lowercase is not allowed by the spec
+        assert_eq!(
+            AsciiFrame::parse(b":1103006b00037E\r\n"),
+            Err(FrameError::InvalidHexCharacter { position: 8, byte: b'b' })
+        );
+        assert_eq!(
+            AsciiFrame::parse(b":11030G6B00037E\r\n"),
+            Err(FrameError::InvalidHexCharacter { position: 6, byte: b'G' })
+        );
+    }
+
+    #[test]
+    fn test_fuzz_odd_length_never_decodes() {
+        // every odd-length body of valid hex digits is rejected before decoding
+        let digits = b"0123456789ABCDEF";
+        for len in (1..64).step_by(2) {
+            let mut data = vec![b':'];
+            data.extend((0..len).map(|i| digits[(i * 7) % 16]));
+            data.extend_from_slice(b"\r\n");
+            if data.len() >= MIN_FRAME_LEN {
+                assert_eq!(AsciiFrame::parse(&data), Err(FrameError::OddHexLength(len)));
+            } else {
+                assert!(AsciiFrame::parse(&data).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_fuzz_non_hex_bytes_rejected() {
+        // inject every non-hex byte value into an otherwise valid frame
+        let valid = b":1103006B00037E\r\n";
+        for byte in 0..=255u8 {
+            if hex_value(byte).is_some() {
+                continue;
+            }
+            for position in 1..valid.len() - 2 {
+                let mut data = valid.to_vec();
+                data[position] = byte;
+                assert_eq!(
+                    AsciiFrame::parse(&data),
+                    Err(FrameError::InvalidHexCharacter { position, byte }),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fuzz_random_input_never_panics() {
+        let mut state: u64 = 0x2545_F491;
+        for _ in 0..2000 {
+            let len = (next(&mut state) % 40) as usize;
+            let mut data = vec![b':'];
+            for _ in 0..len {
+                data.push(b"0123456789ABCDEFxyz:\r\n"[(next(&mut state) % 22) as usize]);
+            }
+            data.extend_from_slice(b"\r\n");
+            let _ = AsciiFrame::parse(&data);
+        }
+    }
+}
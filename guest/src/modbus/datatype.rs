@@ -0,0 +1,369 @@
+// guest/src/modbus/datatype.rs
+// typed decoding of values spread across consecutive 16-bit registers.
+// modbus only defines 16-bit big-endian registers; anything wider is a vendor
+// convention, so the register map names both the type and the order the
+// vendor put the bytes in. orders are written as the big-endian bytes A B C D
+// appear on the wire: abcd is plain big endian, cdab swaps the registers,
+// badc swaps the bytes inside each register, dcba is fully little endian.
+// the same rule extends to 64-bit values and strings of any length.
+
+use serde::Serialize;
+use std::fmt;
+
+/// longest string a single read can carry (125 registers)
+pub const MAX_STRING_REGISTERS: u8 = 125;
+
+/// how a run of registers is interpreted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataType {
+    Uint16,
+    Int16,
+    Uint32,
+    Int32,
+    Uint64,
+    Int64,
+    Float32,
+    Float64,
+    Bcd16,      // 4 packed decimal digits
+    Bcd32,      // 8 packed decimal digits
+    Ascii(u8),  // two characters per register, length in registers
+}
+
+impl DataType {
+    /// number of registers one value occupies
+    pub fn register_count(&self) -> usize {
+        match self {
+            Self::Uint16 | Self::Int16 | Self::Bcd16 => 1,
+            Self::Uint32 | Self::Int32 | Self::Float32 | Self::Bcd32 => 2,
+            Self::Uint64 | Self::Int64 | Self::Float64 => 4,
+            Self::Ascii(registers) => *registers as usize,
+        }
+    }
+
+    /// snake_case name used in payloads
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Uint16 => "uint16",
+            Self::Int16 => "int16",
+            Self::Uint32 => "uint32",
+            Self::Int32 => "int32",
+            Self::Uint64 => "uint64",
+            Self::Int64 => "int64",
+            Self::Float32 => "float32",
+            Self::Float64 => "float64",
+            Self::Bcd16 => "bcd16",
+            Self::Bcd32 => "bcd32",
+            Self::Ascii(_) => "ascii",
+        }
+    }
+}
+
+/// byte order of a multi-byte value across its registers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ByteOrder {
+    Abcd,  // big endian - the modbus default
+    Cdab,  // registers swapped, bytes big endian
+    Badc,  // registers in order, bytes swapped
+    Dcba,  // little endian
+}
+
+impl ByteOrder {
+    fn swaps_registers(self) -> bool {
+        matches!(self, Self::Cdab | Self::Dcba)
+    }
+
+    fn swaps_bytes(self) -> bool {
+        matches!(self, Self::Badc | Self::Dcba)
+    }
+}
+
+/// a decoded point value, serialized as a bare json number or string
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Value {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),  // nan and infinity serialize as null
+    Text(String),
+}
+
+impl Value {
+    /// numeric value as a float - None for text
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Unsigned(v) => Some(v as f64),
+            Self::Signed(v) => Some(v as f64),
+            Self::Float(v) => Some(v),
+            Self::Text(_) => None,
+        }
+    }
+}
+
+/// why a run of registers could not be decoded
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    RegisterCount { expected: usize, actual: usize },
+    InvalidBcdDigit(u8),
+    NonAsciiCharacter(u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RegisterCount { expected, actual } => {
+                write!(f, "expected {} registers, got {}", expected, actual)
+            }
+            Self::InvalidBcdDigit(nibble) => write!(f, "invalid bcd digit 0x{:X}", nibble),
+            Self::NonAsciiCharacter(byte) => write!(f, "non-ascii character 0x{:02X}", byte),
+        }
+    }
+}
+
+/// decode `words` as one value of `data_type` in `order`
+pub fn decode(words: &[u16], data_type: DataType, order: ByteOrder) -> Result<Value, DecodeError> {
+    let expected = data_type.register_count();
+    if words.len() != expected || expected == 0 {
+        return Err(DecodeError::RegisterCount { expected, actual: words.len() });
+    }
+    let bytes = big_endian_bytes(words, order);
+
+    let value = match data_type {
+        DataType::Uint16 => Value::Unsigned(u16::from_be_bytes(array(&bytes)) as u64),
+        DataType::Int16 => Value::Signed(i16::from_be_bytes(array(&bytes)) as i64),
+        DataType::Uint32 => Value::Unsigned(u32::from_be_bytes(array(&bytes)) as u64),
+        DataType::Int32 => Value::Signed(i32::from_be_bytes(array(&bytes)) as i64),
+        DataType::Uint64 => Value::Unsigned(u64::from_be_bytes(array(&bytes))),
+        DataType::Int64 => Value::Signed(i64::from_be_bytes(array(&bytes))),
+        DataType::Float32 => Value::Float(f32::from_be_bytes(array(&bytes)) as f64),
+        DataType::Float64 => Value::Float(f64::from_be_bytes(array(&bytes))),
+        DataType::Bcd16 | DataType::Bcd32 => Value::Unsigned(bcd(&bytes)?),
+        DataType::Ascii(_) => Value::Text(ascii(&bytes)?),
+    };
+    Ok(value)
+}
+
+/// reorder the value's bytes into plain big endian
+fn big_endian_bytes(words: &[u16], order: ByteOrder) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(words.len() * 2);
+    let mut push = |word: u16| {
+        let word = if order.swaps_bytes() { word.swap_bytes() } else { word };
+        bytes.extend_from_slice(&word.to_be_bytes());
+    };
+    if order.swaps_registers() {
+        words.iter().rev().copied().for_each(&mut push);
+    } else {
+        words.iter().copied().for_each(&mut push);
+    }
+    bytes
+}
+
+/// fixed-size copy of a slice whose length decode already checked
+fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut out = [0u8; N];
+    let len = bytes.len().min(N);
+    out[..len].copy_from_slice(&bytes[..len]);
+    out
+}
+
+/// packed bcd, most significant digit first
+fn bcd(bytes: &[u8]) -> Result<u64, DecodeError> {
+    bytes
+        .iter()
+        .flat_map(|&b| [b >> 4, b & 0x0F])
+        .try_fold(0u64, |acc, digit| {
+            if digit > 9 {
+                return Err(DecodeError::InvalidBcdDigit(digit));
+            }
+            Ok(acc * 10 + digit as u64)
+        })
+}
+
+/// printable ascii, with trailing nul / space padding removed
+fn ascii(bytes: &[u8]) -> Result<String, DecodeError> {
+    let end = bytes.iter().rposition(|&b| b != 0 && b != b' ').map_or(0, |i| i + 1);
+    let text = &bytes[..end];
+    if let Some(&bad) = text.iter().find(|&&b| !(0x20..=0x7E).contains(&b)) {
+        return Err(DecodeError::NonAsciiCharacter(bad));
+    }
+    Ok(text.iter().map(|&b| b as char).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::test_util::next;
+
+    const ORDERS: [ByteOrder; 4] = [ByteOrder::Abcd, ByteOrder::Cdab, ByteOrder::Badc, ByteOrder::Dcba];
+
+    /// the words a device using `order` puts on the wire for big-endian `bytes`
+    fn encode(bytes: &[u8], order: ByteOrder) -> Vec<u16> {
+        let mut words: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| {
+                let word = u16::from_be_bytes([pair[0], pair[1]]);
+                if order.swaps_bytes() { word.swap_bytes() } else { word }
+            })
+            .collect();
+        if order.swaps_registers() {
+            words.reverse();
+        }
+        words
+    }
+
+    #[test]
+    fn test_float32_every_order() {
+        // 123.456f32 = 0x42F6E979, bytes A B C D = 42 F6 E9 79
+        let cases = [
+            (ByteOrder::Abcd, [0x42F6, 0xE979]),
+            (ByteOrder::Cdab, [0xE979, 0x42F6]),
+            (ByteOrder::Badc, [0xF642, 0x79E9]),
+            (ByteOrder::Dcba, [0x79E9, 0xF642]),
+        ];
+        for (order, words) in cases {
+            assert_eq!(
+                decode(&words, DataType::Float32, order),
+                Ok(Value::Float(123.456f32 as f64)),
+                "{:?}",
+                order
+            );
+        }
+    }
+
+    #[test]
+    fn test_32_bit_integers_every_order() {
+        // 0xFEDCBA98: -19088744 signed, 4275878552 unsigned
+        let cases = [
+            (ByteOrder::Abcd, [0xFEDC, 0xBA98]),
+            (ByteOrder::Cdab, [0xBA98, 0xFEDC]),
+            (ByteOrder::Badc, [0xDCFE, 0x98BA]),
+            (ByteOrder::Dcba, [0x98BA, 0xDCFE]),
+        ];
+        for (order, words) in cases {
+            assert_eq!(decode(&words, DataType::Uint32, order), Ok(Value::Unsigned(0xFEDC_BA98)), "{:?}", order);
+            assert_eq!(decode(&words, DataType::Int32, order), Ok(Value::Signed(-19_088_744)), "{:?}", order);
+        }
+    }
+
+    #[test]
+    fn test_64_bit_values_every_order() {
+        let cases = [
+            (ByteOrder::Abcd, [0x0102, 0x0304, 0x0506, 0x0708]),
+            (ByteOrder::Cdab, [0x0708, 0x0506, 0x0304, 0x0102]),
+            (ByteOrder::Badc, [0x0201, 0x0403, 0x0605, 0x0807]),
+            (ByteOrder::Dcba, [0x0807, 0x0605, 0x0403, 0x0201]),
+        ];
+        for (order, words) in cases {
+            assert_eq!(decode(&words, DataType::Uint64, order), Ok(Value::Unsigned(0x0102_0304_0506_0708)));
+            assert_eq!(decode(&words, DataType::Int64, order), Ok(Value::Signed(0x0102_0304_0506_0708)));
+        }
+
+        // -1.5f64 = 0xBFF8000000000000
+        let cases = [
+            (ByteOrder::Abcd, [0xBFF8, 0x0000, 0x0000, 0x0000]),
+            (ByteOrder::Cdab, [0x0000, 0x0000, 0x0000, 0xBFF8]),
+            (ByteOrder::Badc, [0xF8BF, 0x0000, 0x0000, 0x0000]),
+            (ByteOrder::Dcba, [0x0000, 0x0000, 0x0000, 0xF8BF]),
+        ];
+        for (order, words) in cases {
+            assert_eq!(decode(&words, DataType::Float64, order), Ok(Value::Float(-1.5)), "{:?}", order);
+        }
+    }
+
+    #[test]
+    fn test_16_bit_values() {
+        // register swapping is meaningless for one register, byte swapping is not
+        for order in ORDERS {
+            let word = if order.swaps_bytes() { 0x0180 } else { 0x8001 };
+            assert_eq!(decode(&[word], DataType::Uint16, order), Ok(Value::Unsigned(0x8001)));
+            assert_eq!(decode(&[word], DataType::Int16, order), Ok(Value::Signed(-32767)));
+        }
+    }
+
+    #[test]
+    fn test_bcd() {
+        assert_eq!(decode(&[0x1234], DataType::Bcd16, ByteOrder::Abcd), Ok(Value::Unsigned(1234)));
+        assert_eq!(decode(&[0x3412], DataType::Bcd16, ByteOrder::Badc), Ok(Value::Unsigned(1234)));
+        for order in ORDERS {
+            let words = encode(&[0x98, 0x76, 0x54, 0x32], order);
+            assert_eq!(decode(&words, DataType::Bcd32, order), Ok(Value::Unsigned(98_765_432)));
+        }
+        assert_eq!(decode(&[0x12A4], DataType::Bcd16, ByteOrder::Abcd), Err(DecodeError::InvalidBcdDigit(0xA)));
+    }
+
+    #[test]
+    fn test_ascii() {
+        // "PUMP-01" padded with a nul, as most devices store it
+        let text = b"PUMP-01\0";
+        for order in ORDERS {
+            let words = encode(text, order);
+            assert_eq!(
+                decode(&words, DataType::Ascii(4), order),
+                Ok(Value::Text("PUMP-01".to_string())),
+                "{:?}",
+                order
+            );
+        }
+        assert_eq!(decode(&[0x2020, 0x0000], DataType::Ascii(2), ByteOrder::Abcd), Ok(Value::Text(String::new())));
+        assert_eq!(
+            decode(&[0x41FF], DataType::Ascii(1), ByteOrder::Abcd),
+            Err(DecodeError::NonAsciiCharacter(0xFF))
+        );
+    }
+
+    #[test]
+    fn test_register_count_must_match() {
+        assert_eq!(
+            decode(&[0x4248], DataType::Float32, ByteOrder::Abcd),
+            Err(DecodeError::RegisterCount { expected: 2, actual: 1 })
+        );
+        assert_eq!(
+            decode(&[], DataType::Ascii(0), ByteOrder::Abcd),
+            Err(DecodeError::RegisterCount { expected: 0, actual: 0 })
+        );
+    }
+
+    #[test]
+    fn test_round_trip_every_type_and_order() {
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        for _ in 0..1000 {
+            let raw = next(&mut state);
+            let bytes = raw.to_be_bytes();
+            for order in ORDERS {
+                let w64 = encode(&bytes, order);
+                let w32 = encode(&bytes[..4], order);
+                assert_eq!(decode(&w64, DataType::Uint64, order), Ok(Value::Unsigned(raw)));
+                assert_eq!(decode(&w64, DataType::Int64, order), Ok(Value::Signed(raw as i64)));
+                assert_eq!(decode(&w32, DataType::Uint32, order), Ok(Value::Unsigned(raw >> 32)));
+                assert_eq!(decode(&w32, DataType::Int32, order), Ok(Value::Signed((raw >> 32) as u32 as i32 as i64)));
+
+                let f64_bits = f64::from_bits(raw);
+                match decode(&w64, DataType::Float64, order) {
+                    Ok(Value::Float(v)) => assert!(v.to_bits() == f64_bits.to_bits() || (v.is_nan() && f64_bits.is_nan())),
+                    other => panic!("float64 decode failed: {:?}", other),
+                }
+                let f32_bits = f32::from_bits((raw >> 32) as u32);
+                match decode(&w32, DataType::Float32, order) {
+                    Ok(Value::Float(v)) => assert!(v == f32_bits as f64 || (v.is_nan() && f32_bits.is_nan())),
+                    other => panic!("float32 decode failed: {:?}", other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_value_serialization() {
+        assert_eq!(serde_json::to_string(&Value::Unsigned(7)).unwrap(), "7");
+        assert_eq!(serde_json::to_string(&Value::Signed(-7)).unwrap(), "-7");
+        assert_eq!(serde_json::to_string(&Value::Float(1.5)).unwrap(), "1.5");
+        assert_eq!(serde_json::to_string(&Value::Float(f64::NAN)).unwrap(), "null");
+        assert_eq!(serde_json::to_string(&Value::Text("ok".into())).unwrap(), "\"ok\"");
+    }
+
+    #[test]
+    fn test_as_f64() {
+        assert_eq!(Value::Unsigned(1000).as_f64(), Some(1000.0));
+        assert_eq!(Value::Signed(-5).as_f64(), Some(-5.0));
+        assert_eq!(Value::Float(0.25).as_f64(), Some(0.25));
+        assert_eq!(Value::Text("x".into()).as_f64(), None);
+    }
+}
@@ -1,5 +1,5 @@
 // guest/src/modbus/frame.rs
-// parses the modbus application protocol (mbap) header from raw tcp bytes.
+// parses and encodes the modbus application protocol (mbap) header.
 // uses nom combinators for fuzz-proof parsing - returns Err on malformed input,
 // never panics, which is critical for wasm sandbox crash containment.
 
@@ -8,6 +8,8 @@ use nom::{
     IResult,
 };
 
+use super::validate::FrameError;
+
 /// mbap header - 7 bytes that wrap every modbus tcp message
 /// this is the tcp-specific wrapper, not part of the serial modbus protocol
 #[derive(Debug, Clone, PartialEq)]
@@ -18,7 +20,25 @@ pub struct MbapHeader {
     pub unit_id: u8,          // slave address (usually 0x01 or 0xFF)
 }
 
+/// size of the mbap header on the wire
+pub const HEADER_LEN: usize = 7;
+
 impl MbapHeader {
+    /// header for a pdu of `pdu_len` bytes. length counts the unit id too,
+    /// so a pdu the length field cannot describe is an error, never a wrapped length.
+    pub fn new(transaction_id: u16, unit_id: u8, pdu_len: usize) -> Result<Self, FrameError> {
+        let length = u16::try_from(pdu_len.saturating_add(1))
+            .map_err(|_| FrameError::FrameTooLong(HEADER_LEN.saturating_add(pdu_len)))?;
+        let header = Self {
+            transaction_id,
+            protocol_id: 0x0000,
+            length,
+            unit_id,
+        };
+        header.validate()?;
+        Ok(header)
+    }
+
     /// parse mbap header from raw bytes using nom combinators.
     /// returns (remaining_bytes, header) on success, or nom error on failure.
     /// this design means malformed input never panics - it returns an error
@@ -39,22 +59,39 @@ impl MbapHeader {
     
     /// validate the header after parsing.
     /// checks protocol id and length field are within modbus spec bounds.
-    pub fn validate(&self) -> Result<(), &'static str> {
+    /// see validate::validate_pdu for the checks against the pdu itself.
+    pub fn validate(&self) -> Result<(), FrameError> {
         // protocol id must be 0x0000 for modbus
         if self.protocol_id != 0x0000 {
-            return Err("invalid protocol id - must be 0x0000 for modbus");
+            return Err(FrameError::InvalidProtocolId(self.protocol_id));
         }
         // length must be at least 2 (unit_id + function code) and at most 253
         if self.length < 2 || self.length > 253 {
-            return Err("invalid length field - must be 2-253");
+            return Err(FrameError::LengthOutOfRange(self.length));
         }
         Ok(())
     }
+
+    /// append the 7 header bytes (all fields big-endian) to `out`
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.transaction_id.to_be_bytes());
+        out.extend_from_slice(&self.protocol_id.to_be_bytes());
+        out.extend_from_slice(&self.length.to_be_bytes());
+        out.push(self.unit_id);
+    }
+
+    /// encode the header into a new buffer with room for a pdu to follow
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.length as usize);
+        self.encode(&mut out);
+        out
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modbus::test_util::next;
     
     #[test]
     fn test_parse_valid_header() {
@@ -86,4 +123,36 @@ mod tests {
         };
         assert!(header.validate().is_err());
     }
+
+    #[test]
+    fn test_encode_known_bytes() {
+        let header = MbapHeader::new(0x1234, 0x11, 5).unwrap();
+        assert_eq!(header.to_bytes(), vec![0x12, 0x34, 0x00, 0x00, 0x00, 0x06, 0x11]);
+
+        // a length the field cannot carry is refused rather than wrapped
+        assert_eq!(MbapHeader::new(1, 1, 252).map(|h| h.length), Ok(253));
+        assert_eq!(MbapHeader::new(1, 1, 253), Err(FrameError::LengthOutOfRange(254)));
+        assert_eq!(MbapHeader::new(1, 1, 0), Err(FrameError::LengthOutOfRange(1)));
+        assert_eq!(MbapHeader::new(1, 1, 65535), Err(FrameError::FrameTooLong(65542)));
+    }
+
+    #[test]
+    fn test_round_trip_property() {
+        // parse(encode(x)) == x over pseudo-random headers, including invalid
+        // protocol ids and lengths - the codec must not care about validity
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        for _ in 0..5000 {
+            let r = next(&mut state);
+            let header = MbapHeader {
+                transaction_id: r as u16,
+                protocol_id: (r >> 16) as u16,
+                length: (r >> 32) as u16,
+                unit_id: (r >> 48) as u8,
+            };
+
+            let bytes = header.to_bytes();
+            assert_eq!(bytes.len(), HEADER_LEN);
+            assert_eq!(MbapHeader::parse(&bytes), Ok((&[][..], header)));
+        }
+    }
 }
@@ -1,46 +1,152 @@
 // guest/src/modbus/function.rs
 // handles modbus function codes. per iec 62443 attack surface minimization,
-// we only implement read-only function codes (0x03, 0x04) for the data conduit.
+// we only implement read-only function codes (0x01-0x04) for the data conduit.
 // all other codes are explicitly rejected - this is intentional security design.
+// exception responses (function code | 0x80) are parsed into a typed error
+// so a plc reporting a fault is not confused with a malformed frame.
+// read device identification (0x2B / mei 0x0E) is the one non-data read,
+// accepted so the gateway can publish an asset inventory.
 
 use nom::{
+    bytes::complete::take,
+    combinator::verify,
     number::complete::{be_u16, be_u8},
     IResult,
 };
 
+use super::validate::FrameError;
+
 /// supported modbus function codes - intentionally limited scope
 /// per iec 62443 principle of minimizing attack surface, we only implement
 /// what's needed for a read-only data conduit
+// variant names follow the modbus spec, where every conduit function is a read
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FunctionCode {
+    ReadCoils,             // 0x01 - read digital outputs
+    ReadDiscreteInputs,    // 0x02 - read digital inputs from field devices
     ReadHoldingRegisters,  // 0x03 - read analog outputs / configuration
     ReadInputRegisters,    // 0x04 - read analog inputs from field devices
+    ReadDeviceIdentification, // 0x2B / mei 0x0E - vendor, product and revision strings
 }
 
 impl FunctionCode {
-    /// parse function code byte. rejects all codes except 0x03 and 0x04.
+    /// parse function code byte. rejects all codes except the reads.
     /// this is not a bug - it's iec 62443 attack surface minimization.
-    /// if someone asks "why only two function codes?", the answer is:
+    /// if someone asks "why only read function codes?", the answer is:
     /// "per iec 62443, we minimize attack surface by only implementing
     /// the minimum required for the data conduit."
+    /// sites narrow this further with the function code policy (see policy.rs).
     pub fn from_byte(byte: u8) -> Option<Self> {
         match byte {
+            0x01 => Some(Self::ReadCoils),
+            0x02 => Some(Self::ReadDiscreteInputs),
             0x03 => Some(Self::ReadHoldingRegisters),
             0x04 => Some(Self::ReadInputRegisters),
+            0x2B => Some(Self::ReadDeviceIdentification),
             _ => None, // intentionally reject all other function codes
         }
     }
     
     /// convert function code to its byte representation
-    pub fn to_byte(&self) -> u8 {
+    pub fn to_byte(self) -> u8 {
         match self {
+            Self::ReadCoils => 0x01,
+            Self::ReadDiscreteInputs => 0x02,
             Self::ReadHoldingRegisters => 0x03,
             Self::ReadInputRegisters => 0x04,
+            Self::ReadDeviceIdentification => 0x2B,
+        }
+    }
+
+    /// snake_case name used in mqtt payloads
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ReadCoils => "read_coils",
+            Self::ReadDiscreteInputs => "read_discrete_inputs",
+            Self::ReadHoldingRegisters => "read_holding_registers",
+            Self::ReadInputRegisters => "read_input_registers",
+            Self::ReadDeviceIdentification => "read_device_identification",
+        }
+    }
+
+    /// true for the single-bit reads (coils and discrete inputs)
+    /// whose responses are bit-packed instead of 16-bit registers
+    pub fn is_bit_access(self) -> bool {
+        matches!(self, Self::ReadCoils | Self::ReadDiscreteInputs)
+    }
+
+    /// true for the 16-bit register reads (holding and input registers)
+    pub fn is_register_access(self) -> bool {
+        matches!(self, Self::ReadHoldingRegisters | Self::ReadInputRegisters)
+    }
+}
+
+/// modbus exception codes returned by a slave in an exception response.
+/// only codes defined in the modbus application protocol spec v1.1b3 are
+/// accepted - anything else is treated as a malformed frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExceptionCode {
+    IllegalFunction,                    // 0x01 - function not supported by slave
+    IllegalDataAddress,                 // 0x02 - address range not valid on slave
+    IllegalDataValue,                   // 0x03 - value in request not allowed
+    ServerDeviceFailure,                // 0x04 - unrecoverable error on slave
+    Acknowledge,                        // 0x05 - long-running request accepted
+    ServerDeviceBusy,                   // 0x06 - slave busy, retry later
+    MemoryParityError,                  // 0x08 - extended file area parity error
+    GatewayPathUnavailable,             // 0x0A - gateway has no path to target
+    GatewayTargetFailedToRespond,       // 0x0B - target behind gateway is silent
+}
+
+impl ExceptionCode {
+    /// parse exception code byte. returns None for codes outside the spec.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0x01 => Some(Self::IllegalFunction),
+            0x02 => Some(Self::IllegalDataAddress),
+            0x03 => Some(Self::IllegalDataValue),
+            0x04 => Some(Self::ServerDeviceFailure),
+            0x05 => Some(Self::Acknowledge),
+            0x06 => Some(Self::ServerDeviceBusy),
+            0x08 => Some(Self::MemoryParityError),
+            0x0A => Some(Self::GatewayPathUnavailable),
+            0x0B => Some(Self::GatewayTargetFailedToRespond),
+            _ => None,
+        }
+    }
+
+    /// convert exception code to its byte representation
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Self::IllegalFunction => 0x01,
+            Self::IllegalDataAddress => 0x02,
+            Self::IllegalDataValue => 0x03,
+            Self::ServerDeviceFailure => 0x04,
+            Self::Acknowledge => 0x05,
+            Self::ServerDeviceBusy => 0x06,
+            Self::MemoryParityError => 0x08,
+            Self::GatewayPathUnavailable => 0x0A,
+            Self::GatewayTargetFailedToRespond => 0x0B,
+        }
+    }
+
+    /// snake_case name used in mqtt payloads
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::IllegalFunction => "illegal_function",
+            Self::IllegalDataAddress => "illegal_data_address",
+            Self::IllegalDataValue => "illegal_data_value",
+            Self::ServerDeviceFailure => "server_device_failure",
+            Self::Acknowledge => "acknowledge",
+            Self::ServerDeviceBusy => "server_device_busy",
+            Self::MemoryParityError => "memory_parity_error",
+            Self::GatewayPathUnavailable => "gateway_path_unavailable",
+            Self::GatewayTargetFailedToRespond => "gateway_target_failed_to_respond",
         }
     }
 }
 
-/// parsed read request (0x03 or 0x04)
+/// parsed read request (0x01-0x04)
 /// sent from master to slave to request register values
 #[derive(Debug, Clone, PartialEq)]
 pub struct ReadRequest {
@@ -49,7 +155,70 @@ pub struct ReadRequest {
     pub quantity: u16,
 }
 
-/// parsed read response (0x03 or 0x04)
+impl ReadRequest {
+    /// parse a read request.
+    /// format: [function_code(1), start_address(2), quantity(2)]
+    /// uses nom for fuzz-proof parsing - returns error on malformed input
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, function_byte) = be_u8(input)?;
+        let function = FunctionCode::from_byte(function_byte)
+            .filter(|f| f.is_bit_access() || f.is_register_access())
+            .ok_or(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Tag,
+            )))?;
+
+        let (input, start_address) = be_u16(input)?;
+        let (input, quantity) = be_u16(input)?;
+
+        Ok((input, Self {
+            function,
+            start_address,
+            quantity,
+        }))
+    }
+
+    /// check quantity against the spec limits for the function and that the
+    /// requested range does not run past address 65535 (the offByOne attack).
+    pub fn validate(&self) -> Result<(), FrameError> {
+        let max_quantity = if self.function.is_bit_access() { 2000 } else { 125 };
+        if self.quantity == 0 || self.quantity > max_quantity {
+            return Err(FrameError::QuantityOutOfRange(self.quantity));
+        }
+        if self.start_address as u32 + self.quantity as u32 > 0x1_0000 {
+            return Err(FrameError::AddressOverflow {
+                start: self.start_address,
+                quantity: self.quantity,
+            });
+        }
+        Ok(())
+    }
+
+    /// number of data bytes a well-formed response to this request carries
+    pub fn expected_byte_count(&self) -> usize {
+        if self.function.is_bit_access() {
+            (self.quantity as usize).div_ceil(8)
+        } else {
+            self.quantity as usize * 2
+        }
+    }
+
+    /// append the 5-byte request pdu to `out`
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.function.to_byte());
+        out.extend_from_slice(&self.start_address.to_be_bytes());
+        out.extend_from_slice(&self.quantity.to_be_bytes());
+    }
+
+    /// encode the request pdu into a new buffer
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(5);
+        self.encode(&mut out);
+        out
+    }
+}
+
+/// parsed read registers response (0x03 or 0x04)
 /// sent from slave to master with requested register values
 #[derive(Debug, Clone, PartialEq)]
 pub struct ReadResponse {
@@ -59,12 +228,27 @@ pub struct ReadResponse {
 }
 
 impl ReadResponse {
+    /// response carrying `registers`, with a byte count that matches them.
+    /// outside the modbus limit of 1-125 registers no byte count could.
+    pub fn new(function: FunctionCode, registers: Vec<u16>) -> Result<Self, FrameError> {
+        if registers.is_empty() || registers.len() > 125 {
+            let quantity = u16::try_from(registers.len()).unwrap_or(u16::MAX);
+            return Err(FrameError::QuantityOutOfRange(quantity));
+        }
+        Ok(Self {
+            function,
+            byte_count: (registers.len() * 2) as u8,
+            registers,
+        })
+    }
+
     /// parse a read holding/input registers response.
     /// format: [function_code(1), byte_count(1), register_values(N*2)]
     /// uses nom for fuzz-proof parsing - returns error on malformed input
     pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
         let (input, function_byte) = be_u8(input)?;
         let function = FunctionCode::from_byte(function_byte)
+            .filter(|f| f.is_register_access())
             .ok_or(nom::Err::Error(nom::error::Error::new(
                 input,
                 nom::error::ErrorKind::Tag,
@@ -89,14 +273,276 @@ impl ReadResponse {
             registers,
         }))
     }
+
+    /// append the response pdu to `out`.
+    /// fields are written as stored - use new() for a consistent byte count.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.function.to_byte());
+        out.push(self.byte_count);
+        for register in &self.registers {
+            out.extend_from_slice(&register.to_be_bytes());
+        }
+    }
+
+    /// encode the response pdu into a new buffer
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(2 + self.registers.len() * 2);
+        self.encode(&mut out);
+        out
+    }
+}
+
+/// parsed read coils / discrete inputs response (0x01 or 0x02)
+/// sent from slave to master with bit-packed on/off states
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadBitsResponse {
+    pub function: FunctionCode,
+    pub byte_count: u8,
+    pub bits: Vec<bool>,  // byte_count * 8 states, including trailing pad bits
+}
+
+impl ReadBitsResponse {
+    /// parse a read coils/discrete inputs response.
+    /// format: [function_code(1), byte_count(1), packed_bits(N)]
+    /// bits are packed lsb-first: the first coil is bit 0 of the first byte.
+    /// the slave pads the last byte with zeros, so the caller trims to the
+    /// requested quantity when it is known.
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, function_byte) = be_u8(input)?;
+        let function = FunctionCode::from_byte(function_byte)
+            .filter(|f| f.is_bit_access())
+            .ok_or(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Tag,
+            )))?;
+
+        let (input, byte_count) = be_u8(input)?;
+        let (remaining, packed) = take(byte_count as usize)(input)?;
+
+        let bits = packed
+            .iter()
+            .flat_map(|&byte| (0..8).map(move |bit| (byte >> bit) & 0x01 == 0x01))
+            .collect();
+
+        Ok((remaining, Self {
+            function,
+            byte_count,
+            bits,
+        }))
+    }
+}
+
+/// mei type for read device identification inside function 0x2B
+pub const MEI_READ_DEVICE_ID: u8 = 0x0E;
+
+/// read device id code - which object category a request/response covers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReadDeviceIdCode {
+    Basic,     // 0x01 - objects 0x00-0x02, mandatory
+    Regular,   // 0x02 - objects 0x03-0x7F, optional
+    Extended,  // 0x03 - objects 0x80-0xFF, vendor private
+    Specific,  // 0x04 - one individual object
+}
+
+impl ReadDeviceIdCode {
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0x01 => Some(Self::Basic),
+            0x02 => Some(Self::Regular),
+            0x03 => Some(Self::Extended),
+            0x04 => Some(Self::Specific),
+            _ => None,
+        }
+    }
+}
+
+/// parsed read device identification request (0x2B / 0x0E)
+/// tracked like a data read so only a requested identity is published
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceIdRequest {
+    pub read_code: ReadDeviceIdCode,
+    pub object_id: u8,
+}
+
+impl DeviceIdRequest {
+    /// parse a read device identification request.
+    /// format: [0x2B, 0x0E, read_code(1), object_id(1)]
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, _) = verify(be_u8, |&b| b == 0x2B)(input)?;
+        let (input, _) = verify(be_u8, |&b| b == MEI_READ_DEVICE_ID)(input)?;
+
+        let (rest, read_code_byte) = be_u8(input)?;
+        let read_code = ReadDeviceIdCode::from_byte(read_code_byte)
+            .ok_or(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Verify,
+            )))?;
+        let (input, object_id) = be_u8(rest)?;
+
+        Ok((input, Self { read_code, object_id }))
+    }
+}
+
+/// one identification object: id + raw value bytes (ascii for standard objects)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceIdObject {
+    pub id: u8,
+    pub value: Vec<u8>,
+}
+
+/// parsed read device identification response (0x2B / 0x0E)
+/// one response may hold only part of the objects - when more_follows is set,
+/// the master asks again starting at next_object_id
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceIdResponse {
+    pub read_code: ReadDeviceIdCode,
+    pub conformity_level: u8,
+    pub more_follows: bool,
+    pub next_object_id: u8,
+    pub objects: Vec<DeviceIdObject>,
+}
+
+impl DeviceIdResponse {
+    /// parse a read device identification response.
+    /// format: [0x2B, 0x0E, read_code(1), conformity(1), more_follows(1),
+    ///          next_object_id(1), object_count(1), {id(1), len(1), value(len)}*]
+    /// every object length is bounds-checked by nom, and nothing may trail
+    /// the last object.
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, _) = verify(be_u8, |&b| b == 0x2B)(input)?;
+        let (input, _) = verify(be_u8, |&b| b == MEI_READ_DEVICE_ID)(input)?;
+
+        let (rest, read_code_byte) = be_u8(input)?;
+        let read_code = ReadDeviceIdCode::from_byte(read_code_byte)
+            .ok_or(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Verify,
+            )))?;
+
+        let (input, conformity_level) = be_u8(rest)?;
+        // more follows is strictly 0x00 (no) or 0xFF (yes)
+        let (input, more_follows) = verify(be_u8, |&b| b == 0x00 || b == 0xFF)(input)?;
+        let (input, next_object_id) = be_u8(input)?;
+        let (input, object_count) = be_u8(input)?;
+
+        let mut objects = Vec::with_capacity(object_count as usize);
+        let mut remaining = input;
+        for _ in 0..object_count {
+            let (input, id) = be_u8(remaining)?;
+            let (input, len) = be_u8(input)?;
+            let (input, value) = take(len as usize)(input)?;
+            objects.push(DeviceIdObject { id, value: value.to_vec() });
+            remaining = input;
+        }
+
+        if !remaining.is_empty() {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                remaining,
+                nom::error::ErrorKind::Eof,
+            )));
+        }
+
+        Ok((remaining, Self {
+            read_code,
+            conformity_level,
+            more_follows: more_follows == 0xFF,
+            next_object_id,
+            objects,
+        }))
+    }
+}
+
+/// parsed exception response (function code | 0x80)
+/// sent from slave to master when a request could not be served
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExceptionResponse {
+    pub function: FunctionCode,  // the function the failed request used
+    pub code: ExceptionCode,
+}
+
+impl ExceptionResponse {
+    /// parse an exception response.
+    /// format: [function_code | 0x80 (1), exception_code(1)]
+    /// the original function code must still be one we support - an exception
+    /// for a write function means someone issued a write through the conduit.
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (rest, function_byte) = be_u8(input)?;
+        if function_byte & 0x80 == 0 {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Tag,
+            )));
+        }
+        let function = FunctionCode::from_byte(function_byte & 0x7F)
+            .ok_or(nom::Err::Error(nom::error::Error::new(
+                rest,
+                nom::error::ErrorKind::Tag,
+            )))?;
+
+        let (remaining, code_byte) = be_u8(rest)?;
+        let code = ExceptionCode::from_byte(code_byte)
+            .ok_or(nom::Err::Error(nom::error::Error::new(
+                rest,
+                nom::error::ErrorKind::Verify,
+            )))?;
+
+        Ok((remaining, Self { function, code }))
+    }
+}
+
+/// any response pdu the gateway accepts from a slave
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponsePdu {
+    Read(ReadResponse),
+    ReadBits(ReadBitsResponse),
+    DeviceId(DeviceIdResponse),
+    Exception(ExceptionResponse),
+}
+
+impl ResponsePdu {
+    /// dispatch on the function code byte.
+    /// exception responses set bit 7, normal responses never do.
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        match input.first() {
+            Some(byte) if byte & 0x80 != 0 => {
+                let (rest, exception) = ExceptionResponse::parse(input)?;
+                Ok((rest, Self::Exception(exception)))
+            }
+            Some(0x01) | Some(0x02) => {
+                let (rest, response) = ReadBitsResponse::parse(input)?;
+                Ok((rest, Self::ReadBits(response)))
+            }
+            Some(0x2B) => {
+                let (rest, response) = DeviceIdResponse::parse(input)?;
+                Ok((rest, Self::DeviceId(response)))
+            }
+            _ => {
+                let (rest, response) = ReadResponse::parse(input)?;
+                Ok((rest, Self::Read(response)))
+            }
+        }
+    }
+
+    /// function code the response answers
+    pub fn function(&self) -> FunctionCode {
+        match self {
+            Self::Read(r) => r.function,
+            Self::ReadBits(r) => r.function,
+            Self::DeviceId(_) => FunctionCode::ReadDeviceIdentification,
+            Self::Exception(e) => e.function,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modbus::test_util::next;
     
     #[test]
     fn test_function_code_parsing() {
+        assert_eq!(FunctionCode::from_byte(0x01), Some(FunctionCode::ReadCoils));
+        assert_eq!(FunctionCode::from_byte(0x02), Some(FunctionCode::ReadDiscreteInputs));
         assert_eq!(FunctionCode::from_byte(0x03), Some(FunctionCode::ReadHoldingRegisters));
         assert_eq!(FunctionCode::from_byte(0x04), Some(FunctionCode::ReadInputRegisters));
         assert_eq!(FunctionCode::from_byte(0x06), None); // write single register - rejected
@@ -120,4 +566,222 @@ mod tests {
         let data = [0xFF, 0x02, 0x00, 0x00];
         assert!(ReadResponse::parse(&data).is_err());
     }
+
+    #[test]
+    fn test_parse_read_request() {
+        // function: 0x04, start: 100, quantity: 10
+        let data = [0x04, 0x00, 0x64, 0x00, 0x0A];
+        let (remaining, request) = ReadRequest::parse(&data).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(request, ReadRequest {
+            function: FunctionCode::ReadInputRegisters,
+            start_address: 100,
+            quantity: 10,
+        });
+        assert_eq!(request.validate(), Ok(()));
+        assert_eq!(request.expected_byte_count(), 20);
+    }
+
+    #[test]
+    fn test_validate_read_request_limits() {
+        // offByOne attack: start 65535, quantity 125
+        let (_, request) = ReadRequest::parse(&[0x03, 0xFF, 0xFF, 0x00, 0x7D]).unwrap();
+        assert_eq!(
+            request.validate(),
+            Err(FrameError::AddressOverflow { start: 0xFFFF, quantity: 125 })
+        );
+
+        // last register exactly at 65535 is fine
+        let (_, request) = ReadRequest::parse(&[0x03, 0xFF, 0xFF, 0x00, 0x01]).unwrap();
+        assert_eq!(request.validate(), Ok(()));
+
+        // 126 registers exceeds the 125 limit, but 2000 coils is allowed
+        let (_, request) = ReadRequest::parse(&[0x03, 0x00, 0x00, 0x00, 0x7E]).unwrap();
+        assert_eq!(request.validate(), Err(FrameError::QuantityOutOfRange(126)));
+        let (_, request) = ReadRequest::parse(&[0x01, 0x00, 0x00, 0x07, 0xD0]).unwrap();
+        assert_eq!(request.validate(), Ok(()));
+        assert_eq!(request.expected_byte_count(), 250);
+
+        // write function codes are never parsed as requests
+        assert!(ReadRequest::parse(&[0x06, 0x00, 0x00, 0x00, 0x01]).is_err());
+    }
+
+    #[test]
+    fn test_parse_read_coils_response() {
+        // function: 0x01, byte_count: 2, bits: 0xCD 0x01
+        // 0xCD = 1100_1101 -> lsb first: 1,0,1,1,0,0,1,1
+        let data = [0x01, 0x02, 0xCD, 0x01];
+        let (remaining, pdu) = ResponsePdu::parse(&data).unwrap();
+        let ResponsePdu::ReadBits(response) = pdu else { panic!("expected bits response") };
+
+        assert!(remaining.is_empty());
+        assert_eq!(response.function, FunctionCode::ReadCoils);
+        assert_eq!(response.bits.len(), 16);
+        assert_eq!(
+            &response.bits[..9],
+            &[true, false, true, true, false, false, true, true, true]
+        );
+        assert!(response.bits[9..].iter().all(|&b| !b)); // zero padding
+    }
+
+    #[test]
+    fn test_reject_truncated_bits_response() {
+        // byte_count claims 3 bytes, only 1 present
+        assert!(ReadBitsResponse::parse(&[0x02, 0x03, 0xFF]).is_err());
+        // register function code is not a bit response
+        assert!(ReadBitsResponse::parse(&[0x03, 0x01, 0xFF]).is_err());
+        // and a bit function code is not a register response
+        assert!(ReadResponse::parse(&[0x01, 0x02, 0x00, 0x01]).is_err());
+    }
+
+    const READ_FUNCTIONS: [FunctionCode; 4] = [
+        FunctionCode::ReadCoils,
+        FunctionCode::ReadDiscreteInputs,
+        FunctionCode::ReadHoldingRegisters,
+        FunctionCode::ReadInputRegisters,
+    ];
+
+    #[test]
+    fn test_encode_known_bytes() {
+        let request = ReadRequest {
+            function: FunctionCode::ReadHoldingRegisters,
+            start_address: 0x006B,
+            quantity: 3,
+        };
+        assert_eq!(request.to_bytes(), vec![0x03, 0x00, 0x6B, 0x00, 0x03]);
+
+        let response = ReadResponse::new(FunctionCode::ReadHoldingRegisters, vec![1000, 2000]).unwrap();
+        assert_eq!(response.to_bytes(), vec![0x03, 0x04, 0x03, 0xE8, 0x07, 0xD0]);
+
+        assert_eq!(
+            ReadResponse::new(FunctionCode::ReadInputRegisters, vec![0; 126]),
+            Err(FrameError::QuantityOutOfRange(126))
+        );
+        assert_eq!(ReadResponse::new(FunctionCode::ReadInputRegisters, Vec::new()), Err(FrameError::QuantityOutOfRange(0)));
+    }
+
+    #[test]
+    fn test_request_round_trip_property() {
+        let mut state = 0x2545_F491_4F6C_DD1D;
+        for _ in 0..5000 {
+            let r = next(&mut state);
+            let request = ReadRequest {
+                function: READ_FUNCTIONS[(r % 4) as usize],
+                start_address: (r >> 8) as u16,
+                quantity: (r >> 24) as u16,
+            };
+
+            let bytes = request.to_bytes();
+            assert_eq!(ReadRequest::parse(&bytes), Ok((&[][..], request)));
+        }
+    }
+
+    #[test]
+    fn test_response_round_trip_property() {
+        let mut state = 0x853C_49E6_748F_EA9B;
+        for _ in 0..2000 {
+            let r = next(&mut state);
+            let function = READ_FUNCTIONS[2 + (r % 2) as usize];
+            let count = 1 + (r >> 8) as usize % 125;
+            let registers = (0..count).map(|_| next(&mut state) as u16).collect();
+            let response = ReadResponse::new(function, registers).unwrap();
+
+            let bytes = response.to_bytes();
+            assert_eq!(ReadResponse::parse(&bytes), Ok((&[][..], response.clone())));
+            // the encoded frame also passes the cross-field validator
+            assert_eq!(crate::modbus::validate::validate_pdu(&bytes), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_parse_device_id_response() {
+        // basic objects, conformity 0x81, no more follows, 3 objects
+        let data = [
+            0x2B, 0x0E, 0x01, 0x81, 0x00, 0x00, 0x03,
+            0x00, 0x07, b'A', b'c', b'm', b'e', b'P', b'L', b'C',
+            0x01, 0x04, b'X', b'-', b'2', b'0',
+            0x02, 0x05, b'v', b'1', b'.', b'2', b'3',
+        ];
+        let (_, pdu) = ResponsePdu::parse(&data).unwrap();
+        let ResponsePdu::DeviceId(response) = pdu else { panic!("expected device id response") };
+
+        assert_eq!(response.read_code, ReadDeviceIdCode::Basic);
+        assert_eq!(response.conformity_level, 0x81);
+        assert!(!response.more_follows);
+        assert_eq!(response.objects.len(), 3);
+        assert_eq!(response.objects[0], DeviceIdObject { id: 0x00, value: b"AcmePLC".to_vec() });
+        assert_eq!(response.objects[2].value, b"v1.23");
+    }
+
+    #[test]
+    fn test_parse_device_id_more_follows() {
+        let data = [0x2B, 0x0E, 0x02, 0x82, 0xFF, 0x04, 0x01, 0x03, 0x02, b'o', b'k'];
+        let (_, response) = DeviceIdResponse::parse(&data).unwrap();
+
+        assert!(response.more_follows);
+        assert_eq!(response.next_object_id, 0x04);
+    }
+
+    #[test]
+    fn test_reject_malformed_device_id() {
+        // object length 0x20 runs past the end of the frame
+        assert!(DeviceIdResponse::parse(&[0x2B, 0x0E, 0x01, 0x81, 0x00, 0x00, 0x01, 0x00, 0x20, b'A']).is_err());
+        // object count says 2, only 1 present
+        assert!(DeviceIdResponse::parse(&[0x2B, 0x0E, 0x01, 0x81, 0x00, 0x00, 0x02, 0x00, 0x01, b'A']).is_err());
+        // trailing byte after the last object
+        assert!(DeviceIdResponse::parse(&[0x2B, 0x0E, 0x01, 0x81, 0x00, 0x00, 0x01, 0x00, 0x01, b'A', 0x00]).is_err());
+        // more follows must be 0x00 or 0xFF
+        assert!(DeviceIdResponse::parse(&[0x2B, 0x0E, 0x01, 0x81, 0x01, 0x00, 0x00]).is_err());
+        // other mei types (e.g. 0x0D canopen) are rejected
+        assert!(DeviceIdResponse::parse(&[0x2B, 0x0D, 0x01, 0x81, 0x00, 0x00, 0x00]).is_err());
+        // device id is never parsed as a data read request
+        assert!(ReadRequest::parse(&[0x2B, 0x0E, 0x01, 0x00, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_parse_device_id_request() {
+        let (rest, request) = DeviceIdRequest::parse(&[0x2B, 0x0E, 0x04, 0x05]).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(request, DeviceIdRequest { read_code: ReadDeviceIdCode::Specific, object_id: 0x05 });
+
+        // unknown read code, other mei type, missing object id
+        assert!(DeviceIdRequest::parse(&[0x2B, 0x0E, 0x05, 0x00]).is_err());
+        assert!(DeviceIdRequest::parse(&[0x2B, 0x0D, 0x01, 0x00]).is_err());
+        assert!(DeviceIdRequest::parse(&[0x2B, 0x0E, 0x01]).is_err());
+    }
+
+    #[test]
+    fn test_parse_exception_response() {
+        // function: 0x83 (0x03 | 0x80), exception: 0x02 illegal data address
+        let data = [0x83, 0x02];
+        let (remaining, pdu) = ResponsePdu::parse(&data).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(pdu, ResponsePdu::Exception(ExceptionResponse {
+            function: FunctionCode::ReadHoldingRegisters,
+            code: ExceptionCode::IllegalDataAddress,
+        }));
+    }
+
+    #[test]
+    fn test_exception_codes_round_trip() {
+        for byte in 0..=0xFFu8 {
+            if let Some(code) = ExceptionCode::from_byte(byte) {
+                assert_eq!(code.to_byte(), byte);
+            }
+        }
+        assert_eq!(ExceptionCode::from_byte(0x07), None); // negative acknowledge - not in spec
+        assert_eq!(ExceptionCode::from_byte(0x00), None);
+    }
+
+    #[test]
+    fn test_reject_exception_for_unsupported_function() {
+        // 0x86 = exception for write single register - never issued by this conduit
+        assert!(ResponsePdu::parse(&[0x86, 0x01]).is_err());
+        // unknown exception code
+        assert!(ResponsePdu::parse(&[0x84, 0x09]).is_err());
+        // truncated - no exception code byte
+        assert!(ResponsePdu::parse(&[0x84]).is_err());
+    }
 }
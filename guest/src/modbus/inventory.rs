@@ -0,0 +1,137 @@
+// guest/src/modbus/inventory.rs
+// collects read device identification objects per unit id.
+// a device may split its objects over several responses ("more follows"),
+// so objects are merged until the final response arrives and only then is a
+// complete identity handed back for publishing.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::function::DeviceIdResponse;
+
+/// cap on object bytes held for one unit while a continuation is pending.
+/// a device can never make the guest buffer more than this per unit id.
+pub const MAX_IDENTITY_BYTES: usize = 2048;
+
+/// standard object ids from the modbus spec (basic + regular categories)
+pub const VENDOR_NAME: u8 = 0x00;
+pub const PRODUCT_CODE: u8 = 0x01;
+pub const MAJOR_MINOR_REVISION: u8 = 0x02;
+pub const VENDOR_URL: u8 = 0x03;
+pub const PRODUCT_NAME: u8 = 0x04;
+pub const MODEL_NAME: u8 = 0x05;
+pub const USER_APPLICATION_NAME: u8 = 0x06;
+
+/// first object id of the vendor-private extended category
+pub const FIRST_EXTENDED_OBJECT: u8 = 0x80;
+
+/// complete identification of one device
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceIdentity {
+    pub conformity_level: u8,
+    pub objects: BTreeMap<u8, Vec<u8>>,
+}
+
+impl DeviceIdentity {
+    /// object value as text. values are ascii per the spec; anything else is
+    /// replaced rather than trusted.
+    pub fn text(&self, id: u8) -> Option<String> {
+        self.objects.get(&id).map(|v| String::from_utf8_lossy(v).into_owned())
+    }
+
+    fn size(&self) -> usize {
+        self.objects.values().map(Vec::len).sum()
+    }
+}
+
+/// partially received identities, keyed by unit id
+#[derive(Debug, Default)]
+pub struct DeviceInventory {
+    partial: HashMap<u8, DeviceIdentity>,
+}
+
+impl DeviceInventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// merge one response into the unit's identity.
+    /// returns the complete identity once the device reports nothing more follows.
+    pub fn apply(&mut self, unit_id: u8, response: DeviceIdResponse) -> Result<Option<DeviceIdentity>, &'static str> {
+        let identity = self.partial.entry(unit_id).or_default();
+        identity.conformity_level = response.conformity_level;
+        for object in response.objects {
+            identity.objects.insert(object.id, object.value);
+        }
+
+        if identity.size() > MAX_IDENTITY_BYTES {
+            self.partial.remove(&unit_id);
+            return Err("device identification exceeds size limit");
+        }
+
+        if response.more_follows {
+            return Ok(None);
+        }
+        Ok(self.partial.remove(&unit_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::function::{DeviceIdObject, ReadDeviceIdCode};
+
+    fn response(more_follows: bool, objects: &[(u8, &[u8])]) -> DeviceIdResponse {
+        DeviceIdResponse {
+            read_code: ReadDeviceIdCode::Regular,
+            conformity_level: 0x82,
+            more_follows,
+            next_object_id: if more_follows { objects.len() as u8 } else { 0 },
+            objects: objects
+                .iter()
+                .map(|&(id, value)| DeviceIdObject { id, value: value.to_vec() })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_single_response_completes() {
+        let mut inventory = DeviceInventory::new();
+        let identity = inventory
+            .apply(1, response(false, &[(VENDOR_NAME, b"Acme"), (PRODUCT_CODE, b"X-20")]))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(identity.text(VENDOR_NAME), Some("Acme".to_string()));
+        assert_eq!(identity.text(MAJOR_MINOR_REVISION), None);
+    }
+
+    #[test]
+    fn test_continuation_is_merged() {
+        let mut inventory = DeviceInventory::new();
+        assert_eq!(inventory.apply(1, response(true, &[(VENDOR_NAME, b"Acme")])), Ok(None));
+        // a different unit does not disturb the pending continuation
+        assert!(inventory.apply(2, response(false, &[(VENDOR_NAME, b"Other")])).unwrap().is_some());
+
+        let identity = inventory
+            .apply(1, response(false, &[(MAJOR_MINOR_REVISION, b"v2.1")]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.objects.len(), 2);
+        assert_eq!(identity.text(MAJOR_MINOR_REVISION), Some("v2.1".to_string()));
+    }
+
+    #[test]
+    fn test_size_limit_drops_partial() {
+        let mut inventory = DeviceInventory::new();
+        let big = [b'x'; 250];
+        let mut result = Ok(None);
+        for id in 0x80..0x90u8 {
+            result = inventory.apply(1, response(true, &[(id, &big)]));
+            if result.is_err() {
+                break;
+            }
+        }
+        assert!(result.is_err());
+        assert!(inventory.partial.is_empty());
+    }
+}
@@ -1,7 +1,20 @@
 // guest/src/modbus/mod.rs
 // modbus protocol parsing module.
-// contains frame parsing (mbap header) and function code handlers.
+// contains tcp (mbap header), rtu (crc16) and ascii (lrc) framing, function code handlers,
+// tcp stream reassembly, cross-field frame validation, request/response
+// correlation, the device identification inventory and typed decoding of
+// multi-register values.
 // uses nom for fuzz-proof parsing - malformed input returns errors, never panics.
 
+pub mod adu;
+pub mod ascii;
+pub mod datatype;
 pub mod frame;
 pub mod function;
+pub mod inventory;
+pub mod rtu;
+pub mod stream;
+#[cfg(test)]
+mod test_util;
+pub mod transaction;
+pub mod validate;
@@ -0,0 +1,153 @@
+// guest/src/modbus/rtu.rs
+// parses modbus rtu frames as seen on an rs-485 serial line.
+// rtu has no mbap header: [slave_address(1)][pdu(N)][crc16 lo(1)][crc16 hi(1)].
+// frames are delimited by line silence, so the host delivers one frame per
+// receive and the crc is the only integrity check - it is always verified.
+
+use super::validate::FrameError;
+
+/// smallest rtu frame: address + function code + 2 crc bytes
+pub const MIN_FRAME_LEN: usize = 4;
+
+/// largest rtu frame allowed by the serial line spec
+pub const MAX_FRAME_LEN: usize = 256;
+
+/// a crc-verified rtu frame borrowing its pdu from the input buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtuFrame<'a> {
+    pub address: u8,   // slave address, 1-247 for unicast replies
+    pub pdu: &'a [u8], // function code + data, crc stripped
+}
+
+impl<'a> RtuFrame<'a> {
+    /// parse and verify one rtu frame.
+    /// checks frame size, crc and slave address before exposing the pdu,
+    /// so nothing downstream ever sees bytes that failed the checksum.
+    pub fn parse(input: &'a [u8]) -> Result<Self, FrameError> {
+        if input.len() < MIN_FRAME_LEN {
+            return Err(FrameError::TruncatedHeader);
+        }
+        if input.len() > MAX_FRAME_LEN {
+            return Err(FrameError::FrameTooLong(input.len()));
+        }
+
+        let (body, crc_bytes) = input.split_at(input.len() - 2);
+        // crc is transmitted low byte first, unlike every other modbus field
+        let received = u16::from_le_bytes([crc_bytes[0], crc_bytes[1]]);
+        let computed = crc16(body);
+        if computed != received {
+            return Err(FrameError::CrcMismatch { computed, received });
+        }
+
+        let address = body[0];
+        // 0 is broadcast (never answered) and 248-255 are reserved
+        if address == 0 || address > 247 {
+            return Err(FrameError::InvalidSlaveAddress(address));
+        }
+
+        Ok(Self {
+            address,
+            pdu: &body[1..],
+        })
+    }
+}
+
+/// build an rtu frame: address + pdu + crc16 (low byte first)
+pub fn encode(address: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(pdu.len() + 3);
+    out.push(address);
+    out.extend_from_slice(pdu);
+    let crc = crc16(&out);
+    out.extend_from_slice(&crc.to_le_bytes());
+    out
+}
+
+/// modbus crc-16: polynomial 0xA001 (reflected 0x8005), initial value 0xFFFF
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in data {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            if crc & 0x0001 != 0 {
+                crc = (crc >> 1) ^ 0xA001;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::function::{FunctionCode, ReadRequest, ResponsePdu};
+
+    #[test]
+    fn test_crc16_known_vectors() {
+        // read 10 holding registers from slave 1 - crc 0xCDC5
+        assert_eq!(crc16(&[0x01, 0x03, 0x00, 0x00, 0x00, 0x0A]), 0xCDC5);
+        // modbus spec example: slave 0x11 reads 3 registers at 0x006B
+        assert_eq!(crc16(&[0x11, 0x03, 0x00, 0x6B, 0x00, 0x03]), 0x8776);
+    }
+
+    #[test]
+    fn test_parse_captured_request() {
+        let data = [0x11, 0x03, 0x00, 0x6B, 0x00, 0x03, 0x76, 0x87];
+        let frame = RtuFrame::parse(&data).unwrap();
+
+        assert_eq!(frame.address, 0x11);
+        let (_, request) = ReadRequest::parse(frame.pdu).unwrap();
+        assert_eq!(request, ReadRequest {
+            function: FunctionCode::ReadHoldingRegisters,
+            start_address: 0x6B,
+            quantity: 3,
+        });
+    }
+
+    #[test]
+    fn test_parse_response_feeds_read_response() {
+        // slave 1, 0x03, byte_count 4, registers [1000, 2000] + crc
+        let mut data = vec![0x01, 0x03, 0x04, 0x03, 0xE8, 0x07, 0xD0];
+        let crc = crc16(&data);
+        data.extend_from_slice(&crc.to_le_bytes());
+
+        let frame = RtuFrame::parse(&data).unwrap();
+        let (_, pdu) = ResponsePdu::parse(frame.pdu).unwrap();
+        let ResponsePdu::Read(response) = pdu else { panic!("expected read response") };
+        assert_eq!(response.registers, vec![1000, 2000]);
+    }
+
+    #[test]
+    fn test_encode_matches_captured_request() {
+        let frame = encode(0x11, &[0x03, 0x00, 0x6B, 0x00, 0x03]);
+        assert_eq!(frame, vec![0x11, 0x03, 0x00, 0x6B, 0x00, 0x03, 0x76, 0x87]);
+        assert_eq!(RtuFrame::parse(&frame).unwrap().pdu, &frame[1..6]);
+    }
+
+    #[test]
+    fn test_reject_bad_crc() {
+        // last crc byte flipped
+        let data = [0x11, 0x03, 0x00, 0x6B, 0x00, 0x03, 0x76, 0x88];
+        assert_eq!(
+            RtuFrame::parse(&data),
+            Err(FrameError::CrcMismatch { computed: 0x8776, received: 0x8876 })
+        );
+
+        // single bit flip in the payload
+        let data = [0x11, 0x03, 0x00, 0x6A, 0x00, 0x03, 0x76, 0x87];
+        assert!(matches!(RtuFrame::parse(&data), Err(FrameError::CrcMismatch { .. })));
+    }
+
+    #[test]
+    fn test_reject_bad_size_and_address() {
+        assert_eq!(RtuFrame::parse(&[0x01, 0x03, 0x00]), Err(FrameError::TruncatedHeader));
+        assert_eq!(RtuFrame::parse(&[0u8; 300]), Err(FrameError::FrameTooLong(300)));
+
+        // broadcast address with a valid crc
+        let mut data = vec![0x00, 0x83, 0x02];
+        let crc = crc16(&data);
+        data.extend_from_slice(&crc.to_le_bytes());
+        assert_eq!(RtuFrame::parse(&data), Err(FrameError::InvalidSlaveAddress(0)));
+    }
+}
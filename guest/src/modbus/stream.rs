@@ -0,0 +1,181 @@
+// guest/src/modbus/stream.rs
+// reassembles modbus tcp frames from an arbitrary byte stream.
+// a tcp socket delivers chunks, not frames: one frame can arrive in pieces
+// and one chunk can hold several frames. the mbap length field cuts frames,
+// and a header with an impossible protocol id or length is skipped byte by
+// byte until a plausible header lines up again.
+
+use super::frame::{MbapHeader, HEADER_LEN};
+
+/// hard cap on buffered bytes - one maximum-size modbus tcp frame.
+/// a slow-drip attacker can never make the guest hold more than this.
+pub const MAX_BUFFERED: usize = 260;
+
+/// bytes of mbap header before the length-counted part (tid + pid + length)
+const LENGTH_PREFIX: usize = 6;
+
+/// something the reassembler produced from incoming bytes
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    Frame(Vec<u8>),    // one complete mbap frame, ready for adu::decode
+    Discarded(usize),  // bytes skipped while resynchronizing
+}
+
+/// buffers a partial frame between chunks
+#[derive(Debug, Default)]
+pub struct StreamReassembler {
+    buffer: Vec<u8>,
+}
+
+impl StreamReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// feed a chunk from the socket and collect every event it completes.
+    /// the chunk is consumed in slices that fit the cap, so even a huge chunk
+    /// never grows the buffer past MAX_BUFFERED.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<StreamEvent> {
+        let mut events = Vec::new();
+        let mut input = chunk;
+
+        loop {
+            let space = MAX_BUFFERED - self.buffer.len();
+            let (now, later) = input.split_at(space.min(input.len()));
+            self.buffer.extend_from_slice(now);
+            input = later;
+
+            self.drain(&mut events);
+            if input.is_empty() {
+                return events;
+            }
+        }
+    }
+
+    /// drop any partial frame, e.g. when the connection is lost
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// cut complete frames off the front of the buffer
+    fn drain(&mut self, events: &mut Vec<StreamEvent>) {
+        let mut discarded = 0;
+
+        while self.buffer.len() >= HEADER_LEN {
+            let Ok((_, header)) = MbapHeader::parse(&self.buffer) else {
+                break;
+            };
+
+            // impossible header: slide forward one byte and look again
+            if header.validate().is_err() {
+                self.buffer.remove(0);
+                discarded += 1;
+                continue;
+            }
+
+            let total = LENGTH_PREFIX + header.length as usize;
+            if self.buffer.len() < total {
+                break;
+            }
+
+            if discarded > 0 {
+                events.push(StreamEvent::Discarded(discarded));
+                discarded = 0;
+            }
+            events.push(StreamEvent::Frame(self.buffer.drain(..total).collect()));
+        }
+
+        if discarded > 0 {
+            events.push(StreamEvent::Discarded(discarded));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // tid 1, unit 1, 0x03, byte_count 4, registers [1000, 2000]
+    const FRAME: [u8; 13] = [
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x07, 0x01, 0x03, 0x04, 0x03, 0xE8, 0x07, 0xD0,
+    ];
+
+    #[test]
+    fn test_split_frame() {
+        let mut stream = StreamReassembler::new();
+
+        assert!(stream.push(&FRAME[..4]).is_empty());
+        assert!(stream.push(&FRAME[4..9]).is_empty());
+        assert_eq!(stream.push(&FRAME[9..]), vec![StreamEvent::Frame(FRAME.to_vec())]);
+        assert!(stream.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_coalesced_frames() {
+        let mut stream = StreamReassembler::new();
+        let mut chunk = FRAME.to_vec();
+        chunk.extend_from_slice(&FRAME);
+        chunk.extend_from_slice(&FRAME[..3]);
+
+        assert_eq!(
+            stream.push(&chunk),
+            vec![StreamEvent::Frame(FRAME.to_vec()), StreamEvent::Frame(FRAME.to_vec())]
+        );
+        // the partial third frame completes with the next chunk
+        assert_eq!(stream.push(&FRAME[3..]), vec![StreamEvent::Frame(FRAME.to_vec())]);
+    }
+
+    #[test]
+    fn test_resync_after_garbage() {
+        let mut stream = StreamReassembler::new();
+        let mut chunk = vec![0xDE, 0xAD, 0xBE];
+        chunk.extend_from_slice(&FRAME);
+
+        assert_eq!(
+            stream.push(&chunk),
+            vec![StreamEvent::Discarded(3), StreamEvent::Frame(FRAME.to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_impossible_length_is_skipped() {
+        // the massiveLength attack: length 0xFFFF must not make us wait for 64k bytes
+        let mut stream = StreamReassembler::new();
+        let mut chunk = vec![0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x01, 0x03];
+        chunk.extend_from_slice(&FRAME);
+
+        let events = stream.push(&chunk);
+        assert_eq!(events.last(), Some(&StreamEvent::Frame(FRAME.to_vec())));
+        assert!(matches!(events[0], StreamEvent::Discarded(_)));
+    }
+
+    #[test]
+    fn test_slow_drip_is_bounded() {
+        // a header claiming 253 bytes, then one byte at a time forever
+        let mut stream = StreamReassembler::new();
+        stream.push(&[0x00, 0x01, 0x00, 0x00, 0x00, 0xFD]);
+        for _ in 0..10_000 {
+            stream.push(&[0x00]);
+            assert!(stream.buffer.len() <= MAX_BUFFERED);
+        }
+    }
+
+    #[test]
+    fn test_large_chunk_never_exceeds_cap() {
+        let mut stream = StreamReassembler::new();
+        let chunk: Vec<u8> = FRAME.iter().copied().cycle().take(FRAME.len() * 100).collect();
+
+        let events = stream.push(&chunk);
+        assert_eq!(events.len(), 100);
+        assert!(stream.buffer.capacity() <= MAX_BUFFERED * 2);
+    }
+
+    #[test]
+    fn test_reset_drops_partial_frame() {
+        let mut stream = StreamReassembler::new();
+        stream.push(&FRAME[..8]);
+        stream.reset();
+        // the tail of the old frame is too short to look like a header
+        assert!(stream.push(&FRAME[8..]).is_empty());
+    }
+}
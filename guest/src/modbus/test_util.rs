@@ -0,0 +1,10 @@
+// guest/src/modbus/test_util.rs
+// shared helpers for the codec property and fuzz tests.
+
+/// xorshift step - deterministic pseudo-random input without a rand dependency
+pub fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
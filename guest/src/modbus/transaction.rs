@@ -0,0 +1,263 @@
+// guest/src/modbus/transaction.rs
+// correlates responses with the requests that produced them.
+// a modbus response carries no addresses - only the request knows where the
+// registers start - so every response is matched on transaction id + unit id
+// before it is published. unmatched and replayed responses are flagged.
+// device identification requests carry no addresses, so only their keys are
+// kept - enough to refuse an identity nobody asked for.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::function::{ReadRequest, ResponsePdu};
+use super::validate::FrameError;
+
+/// maximum in-flight requests tracked before the oldest is evicted.
+/// bounds memory if a slave never answers or a host floods requests.
+pub const MAX_PENDING: usize = 64;
+
+/// number of answered transactions remembered for duplicate detection
+pub const MAX_COMPLETED: usize = 64;
+
+/// identifies one modbus tcp transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransactionKey {
+    pub transaction_id: u16,
+    pub unit_id: u8,
+}
+
+/// outcome of looking up a response in the transaction table
+#[derive(Debug, Clone, PartialEq)]
+pub enum Correlation {
+    Matched(ReadRequest),  // answers an in-flight request
+    Unmatched,             // no request with this key was ever seen
+    Duplicate,             // key was already answered - retransmit or replay
+}
+
+/// in-flight requests keyed by transaction id + unit id
+#[derive(Debug, Default)]
+pub struct TransactionTable {
+    pending: HashMap<TransactionKey, ReadRequest>,
+    order: VecDeque<TransactionKey>,      // insertion order, oldest first
+    completed: VecDeque<TransactionKey>,  // recently answered, oldest first
+    identifying: VecDeque<TransactionKey>,  // pending device identification, oldest first
+}
+
+impl TransactionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// record a request that was sent to a slave.
+    /// reusing a pending key replaces the old request (the master gave up on it).
+    pub fn insert(&mut self, key: TransactionKey, request: ReadRequest) {
+        if self.pending.insert(key, request).is_some() {
+            self.order.retain(|k| *k != key);
+        } else if self.pending.len() > MAX_PENDING {
+            if let Some(oldest) = self.order.pop_front() {
+                self.pending.remove(&oldest);
+            }
+        }
+        self.order.push_back(key);
+        self.identifying.retain(|k| *k != key);
+        // transaction ids wrap around, so a new request clears the old answer
+        self.completed.retain(|k| *k != key);
+    }
+
+    /// record a read device identification request sent to a slave
+    pub fn insert_identification(&mut self, key: TransactionKey) {
+        self.cancel(key);
+        if self.identifying.len() == MAX_PENDING {
+            self.identifying.pop_front();
+        }
+        self.identifying.push_back(key);
+    }
+
+    /// retire the identification request a response answers -
+    /// false when none is pending
+    pub fn complete_identification(&mut self, key: TransactionKey) -> bool {
+        let pending = self.identifying.len();
+        self.identifying.retain(|k| *k != key);
+        self.identifying.len() < pending
+    }
+
+    /// the pending request for a key, without retiring it
+    pub fn get(&self, key: &TransactionKey) -> Option<&ReadRequest> {
+        self.pending.get(key)
+    }
+
+    /// forget a pending request that will never be answered (e.g. a timed
+    /// out poll), so a late reply is reported as unmatched
+    pub fn cancel(&mut self, key: TransactionKey) {
+        if self.pending.remove(&key).is_some() {
+            self.order.retain(|k| *k != key);
+        }
+        self.identifying.retain(|k| *k != key);
+    }
+
+    /// match a response against the pending requests and retire the entry
+    pub fn complete(&mut self, key: TransactionKey) -> Correlation {
+        if let Some(request) = self.pending.remove(&key) {
+            self.order.retain(|k| *k != key);
+            if self.completed.len() == MAX_COMPLETED {
+                self.completed.pop_front();
+            }
+            self.completed.push_back(key);
+            return Correlation::Matched(request);
+        }
+        if self.completed.contains(&key) {
+            Correlation::Duplicate
+        } else {
+            Correlation::Unmatched
+        }
+    }
+}
+
+/// check that a response actually answers the request it was matched to:
+/// same function code and exactly the requested number of points.
+pub fn verify_response(request: &ReadRequest, response: &ResponsePdu) -> Result<(), FrameError> {
+    let function = response.function();
+    if function != request.function {
+        return Err(FrameError::FunctionMismatch {
+            requested: request.function.to_byte(),
+            received: function.to_byte(),
+        });
+    }
+
+    let received = match response {
+        ResponsePdu::Read(r) => r.byte_count as usize,
+        ResponsePdu::ReadBits(r) => r.byte_count as usize,
+        // exceptions carry no data, and device id never matches a data read
+        ResponsePdu::DeviceId(_) | ResponsePdu::Exception(_) => return Ok(()),
+    };
+    if received != request.expected_byte_count() {
+        return Err(FrameError::QuantityMismatch {
+            requested: request.quantity,
+            received,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::function::FunctionCode;
+
+    fn key(transaction_id: u16) -> TransactionKey {
+        TransactionKey { transaction_id, unit_id: 1 }
+    }
+
+    fn request(start_address: u16, quantity: u16) -> ReadRequest {
+        ReadRequest {
+            function: FunctionCode::ReadHoldingRegisters,
+            start_address,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn test_match_then_duplicate() {
+        let mut table = TransactionTable::new();
+        table.insert(key(1), request(100, 2));
+
+        assert_eq!(table.complete(key(1)), Correlation::Matched(request(100, 2)));
+        assert_eq!(table.complete(key(1)), Correlation::Duplicate);
+        assert_eq!(table.complete(key(2)), Correlation::Unmatched);
+        assert_eq!(table.pending.len(), 0);
+    }
+
+    #[test]
+    fn test_unit_id_is_part_of_key() {
+        let mut table = TransactionTable::new();
+        table.insert(key(7), request(0, 1));
+
+        let other_unit = TransactionKey { transaction_id: 7, unit_id: 2 };
+        assert_eq!(table.complete(other_unit), Correlation::Unmatched);
+        assert!(matches!(table.complete(key(7)), Correlation::Matched(_)));
+    }
+
+    #[test]
+    fn test_reused_transaction_id_after_completion() {
+        let mut table = TransactionTable::new();
+        table.insert(key(1), request(0, 1));
+        table.complete(key(1));
+
+        // tid wraps around and is used again for a new poll
+        table.insert(key(1), request(50, 1));
+        assert_eq!(table.complete(key(1)), Correlation::Matched(request(50, 1)));
+    }
+
+    #[test]
+    fn test_cancelled_request_is_unmatched() {
+        let mut table = TransactionTable::new();
+        table.insert(key(3), request(0, 1));
+        table.cancel(key(3));
+
+        assert_eq!(table.pending.len(), 0);
+        assert!(table.order.is_empty());
+        assert_eq!(table.complete(key(3)), Correlation::Unmatched);
+    }
+
+    #[test]
+    fn test_pending_table_is_bounded() {
+        let mut table = TransactionTable::new();
+        for tid in 0..(MAX_PENDING as u16 + 10) {
+            table.insert(key(tid), request(tid, 1));
+        }
+
+        assert_eq!(table.pending.len(), MAX_PENDING);
+        // the oldest requests were evicted
+        assert_eq!(table.complete(key(0)), Correlation::Unmatched);
+        assert!(matches!(table.complete(key(MAX_PENDING as u16 + 9)), Correlation::Matched(_)));
+    }
+
+    #[test]
+    fn test_identification_needs_a_request() {
+        let mut table = TransactionTable::new();
+        assert!(!table.complete_identification(key(5)));
+
+        table.insert_identification(key(5));
+        assert!(table.complete_identification(key(5)));
+        assert!(!table.complete_identification(key(5)));
+
+        // a data read reusing the key replaces the identification request
+        table.insert_identification(key(6));
+        table.insert(key(6), request(0, 1));
+        assert!(!table.complete_identification(key(6)));
+
+        for tid in 0..(MAX_PENDING as u16 + 1) {
+            table.insert_identification(key(tid));
+        }
+        assert_eq!(table.identifying.len(), MAX_PENDING);
+        assert!(!table.complete_identification(key(0)));
+    }
+
+    #[test]
+    fn test_verify_response() {
+        let (_, two_registers) = ResponsePdu::parse(&[0x03, 0x04, 0x00, 0x01, 0x00, 0x02]).unwrap();
+        assert_eq!(verify_response(&request(0, 2), &two_registers), Ok(()));
+        assert_eq!(
+            verify_response(&request(0, 3), &two_registers),
+            Err(FrameError::QuantityMismatch { requested: 3, received: 4 })
+        );
+
+        let input_request = ReadRequest {
+            function: FunctionCode::ReadInputRegisters,
+            start_address: 0,
+            quantity: 2,
+        };
+        assert_eq!(
+            verify_response(&input_request, &two_registers),
+            Err(FrameError::FunctionMismatch { requested: 0x04, received: 0x03 })
+        );
+
+        // 10 coils fit in 2 bytes
+        let coil_request = ReadRequest {
+            function: FunctionCode::ReadCoils,
+            start_address: 0,
+            quantity: 10,
+        };
+        let (_, coils) = ResponsePdu::parse(&[0x01, 0x02, 0xFF, 0x03]).unwrap();
+        assert_eq!(verify_response(&coil_request, &coils), Ok(()));
+    }
+}
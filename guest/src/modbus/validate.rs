@@ -0,0 +1,251 @@
+// guest/src/modbus/validate.rs
+// cross-field validation of a complete modbus tcp frame.
+// nom proves each field can be read; this module proves the fields agree with
+// each other and with the bytes actually received. every violation has its own
+// error so an attack like a length lie is reported as exactly that.
+
+use std::fmt;
+
+use super::frame::MbapHeader;
+
+/// largest byte_count a read response may carry (125 registers * 2)
+pub const MAX_BYTE_COUNT: u8 = 250;
+
+/// a specific reason a frame failed validation
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameError {
+    TruncatedHeader,                                        // too short to hold the framing fields
+    FrameTooLong(usize),                                    // larger than the framing allows
+    CrcMismatch { computed: u16, received: u16 },           // rtu checksum failed
+    InvalidSlaveAddress(u8),                                // serial address outside 1-247
+    MissingStartDelimiter,                                  // ascii frame does not start with ':'
+    MissingEndDelimiter,                                    // ascii frame does not end with crlf
+    OddHexLength(usize),                                    // ascii body is not whole hex pairs
+    InvalidHexCharacter { position: usize, byte: u8 },      // ascii body byte outside 0-9 A-F
+    LrcMismatch { computed: u8, received: u8 },             // ascii checksum failed
+    InvalidProtocolId(u16),                                 // not 0x0000
+    LengthOutOfRange(u16),                                  // outside 2-253
+    LengthExceedsFrame { declared: u16, actual: usize },    // header promises bytes that never arrived
+    TrailingBytes { expected: usize, actual: usize },       // bytes beyond the declared end
+    TruncatedPdu,                                           // pdu too short to hold its own fields
+    ByteCountTooLarge(u8),                                  // more than 250 data bytes
+    EmptyResponse,                                          // read response with a byte count of 0
+    OddByteCount(u8),                                       // registers are 2 bytes each
+    ByteCountMismatch { byte_count: u8, available: usize }, // byte_count disagrees with data present
+    QuantityOutOfRange(u16),                                // read quantity outside spec limits
+    AddressOverflow { start: u16, quantity: u16 },          // request runs past address 65535
+    FunctionMismatch { requested: u8, received: u8 },       // response answers a different function
+    QuantityMismatch { requested: u16, received: usize },   // response size disagrees with request
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TruncatedHeader => write!(f, "frame truncated before end of header"),
+            Self::FrameTooLong(len) => write!(f, "frame of {} bytes exceeds framing limit", len),
+            Self::CrcMismatch { computed, received } => {
+                write!(f, "crc mismatch: computed 0x{:04X}, received 0x{:04X}", computed, received)
+            }
+            Self::InvalidSlaveAddress(address) => {
+                write!(f, "invalid slave address {} - must be 1-247", address)
+            }
+            Self::MissingStartDelimiter => write!(f, "ascii frame missing ':' start delimiter"),
+            Self::MissingEndDelimiter => write!(f, "ascii frame missing crlf end delimiter"),
+            Self::OddHexLength(len) => write!(f, "ascii frame has odd hex length {}", len),
+            Self::InvalidHexCharacter { position, byte } => {
+                write!(f, "invalid hex character 0x{:02X} at position {}", byte, position)
+            }
+            Self::LrcMismatch { computed, received } => {
+                write!(f, "lrc mismatch: computed 0x{:02X}, received 0x{:02X}", computed, received)
+            }
+            Self::InvalidProtocolId(id) => {
+                write!(f, "invalid protocol id 0x{:04X} - must be 0x0000 for modbus", id)
+            }
+            Self::LengthOutOfRange(length) => {
+                write!(f, "invalid length field {} - must be 2-253", length)
+            }
+            Self::LengthExceedsFrame { declared, actual } => {
+                write!(f, "length field declares {} bytes but frame carries {}", declared, actual)
+            }
+            Self::TrailingBytes { expected, actual } => {
+                write!(f, "{} trailing bytes after {}-byte message", actual - expected, expected)
+            }
+            Self::TruncatedPdu => write!(f, "pdu truncated before byte count"),
+            Self::ByteCountTooLarge(count) => {
+                write!(f, "byte count {} exceeds maximum of {}", count, MAX_BYTE_COUNT)
+            }
+            Self::EmptyResponse => write!(f, "read response carries no data - byte count is 0"),
+            Self::OddByteCount(count) => {
+                write!(f, "odd byte count {} for 16-bit register response", count)
+            }
+            Self::ByteCountMismatch { byte_count, available } => {
+                write!(f, "byte count {} but only {} data bytes present", byte_count, available)
+            }
+            Self::QuantityOutOfRange(quantity) => {
+                write!(f, "read quantity {} outside modbus limits", quantity)
+            }
+            Self::AddressOverflow { start, quantity } => {
+                write!(f, "request for {} points at {} runs past address 65535", quantity, start)
+            }
+            Self::FunctionMismatch { requested, received } => {
+                write!(f, "response function 0x{:02X} does not match request 0x{:02X}", received, requested)
+            }
+            Self::QuantityMismatch { requested, received } => {
+                write!(f, "response carries {} data bytes for a request of {} points", received, requested)
+            }
+        }
+    }
+}
+
+/// validate the mbap header against the bytes that follow it.
+/// `pdu` is everything after the 7-byte mbap header. a length that lies
+/// about the bytes present is reported before the header's own bounds.
+pub fn validate_mbap(header: &MbapHeader, pdu: &[u8]) -> Result<(), FrameError> {
+    // length counts the unit id byte plus the pdu
+    let actual = pdu.len() + 1;
+    let declared = header.length as usize;
+    if declared > actual {
+        return Err(FrameError::LengthExceedsFrame { declared: header.length, actual });
+    }
+    if declared < actual {
+        return Err(FrameError::TrailingBytes { expected: declared, actual });
+    }
+
+    header.validate()
+}
+
+/// validate a response pdu independent of its framing.
+/// function codes the gateway does not understand are left to the parser,
+/// which rejects them as illegal functions.
+pub fn validate_pdu(pdu: &[u8]) -> Result<(), FrameError> {
+    let Some(&function) = pdu.first() else {
+        return Err(FrameError::TruncatedPdu);
+    };
+
+    // exception response: [function | 0x80, exception_code]
+    if function & 0x80 != 0 {
+        return match pdu.len() {
+            0..=1 => Err(FrameError::TruncatedPdu),
+            2 => Ok(()),
+            actual => Err(FrameError::TrailingBytes { expected: 2, actual }),
+        };
+    }
+
+    if !matches!(function, 0x01..=0x04) {
+        return Ok(());
+    }
+
+    let Some(&byte_count) = pdu.get(1) else {
+        return Err(FrameError::TruncatedPdu);
+    };
+    // every read asks for at least one point
+    if byte_count == 0 {
+        return Err(FrameError::EmptyResponse);
+    }
+    if byte_count > MAX_BYTE_COUNT {
+        return Err(FrameError::ByteCountTooLarge(byte_count));
+    }
+    // coils and discrete inputs pack 8 points per byte, so only registers must be even
+    if matches!(function, 0x03 | 0x04) && byte_count % 2 != 0 {
+        return Err(FrameError::OddByteCount(byte_count));
+    }
+
+    let available = pdu.len() - 2;
+    let expected = byte_count as usize;
+    if available < expected {
+        return Err(FrameError::ByteCountMismatch { byte_count, available });
+    }
+    if available > expected {
+        return Err(FrameError::TrailingBytes { expected: expected + 2, actual: pdu.len() });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(frame: &[u8]) -> (MbapHeader, &[u8]) {
+        let (pdu, header) = MbapHeader::parse(frame).unwrap();
+        (header, pdu)
+    }
+
+    fn validate_frame(header: &MbapHeader, pdu: &[u8]) -> Result<(), FrameError> {
+        validate_mbap(header, pdu)?;
+        validate_pdu(pdu)
+    }
+
+    #[test]
+    fn test_valid_frame() {
+        // length 7 = unit + fc + byte_count + 4 data bytes
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0x07, 0x01, 0x03, 0x04, 0x03, 0xE8, 0x07, 0xD0];
+        let (header, pdu) = split(&frame);
+        assert_eq!(validate_frame(&header, pdu), Ok(()));
+    }
+
+    #[test]
+    fn test_buffer_overflow_attack() {
+        // the bufferOverflow chaos attack: length 255, only fc byte follows
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0xFF, 0x01, 0x03];
+        let (header, pdu) = split(&frame);
+        assert_eq!(
+            validate_frame(&header, pdu),
+            Err(FrameError::LengthExceedsFrame { declared: 255, actual: 2 })
+        );
+    }
+
+    #[test]
+    fn test_trailing_bytes_after_length() {
+        // length 3 but 4 pdu bytes follow the unit id
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x01, 0x03, 0x00, 0x00, 0x00];
+        let (header, pdu) = split(&frame);
+        assert_eq!(
+            validate_frame(&header, pdu),
+            Err(FrameError::TrailingBytes { expected: 3, actual: 5 })
+        );
+    }
+
+    #[test]
+    fn test_zero_length_out_of_range() {
+        // consistent with the bytes present, but below the modbus minimum
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x01];
+        let (header, pdu) = split(&frame);
+        assert_eq!(validate_frame(&header, pdu), Err(FrameError::LengthOutOfRange(1)));
+    }
+
+    #[test]
+    fn test_wrong_protocol_reported_first() {
+        let frame = [0x00, 0x01, 0xDE, 0xAD, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A];
+        let (header, pdu) = split(&frame);
+        assert_eq!(validate_frame(&header, pdu), Err(FrameError::InvalidProtocolId(0xDEAD)));
+    }
+
+    #[test]
+    fn test_pdu_violations() {
+        assert_eq!(validate_pdu(&[]), Err(FrameError::TruncatedPdu));
+        assert_eq!(validate_pdu(&[0x03]), Err(FrameError::TruncatedPdu));
+        assert_eq!(validate_pdu(&[0x03, 0xFC]), Err(FrameError::ByteCountTooLarge(0xFC)));
+        assert_eq!(validate_pdu(&[0x03, 0x00]), Err(FrameError::EmptyResponse));
+        assert_eq!(validate_pdu(&[0x01, 0x00]), Err(FrameError::EmptyResponse));
+        assert_eq!(validate_pdu(&[0x03, 0x03, 0x00, 0x01, 0x02]), Err(FrameError::OddByteCount(3)));
+        assert_eq!(
+            validate_pdu(&[0x04, 0x04, 0x00, 0x01]),
+            Err(FrameError::ByteCountMismatch { byte_count: 4, available: 2 })
+        );
+        assert_eq!(
+            validate_pdu(&[0x03, 0x02, 0x00, 0x01, 0xFF]),
+            Err(FrameError::TrailingBytes { expected: 4, actual: 5 })
+        );
+        assert_eq!(
+            validate_pdu(&[0x83, 0x02, 0x00]),
+            Err(FrameError::TrailingBytes { expected: 2, actual: 3 })
+        );
+    }
+
+    #[test]
+    fn test_bit_responses_allow_odd_byte_count() {
+        assert_eq!(validate_pdu(&[0x01, 0x03, 0xFF, 0x00, 0x01]), Ok(()));
+        assert_eq!(validate_pdu(&[0x82, 0x02]), Ok(()));
+    }
+}
@@ -1,5 +1,26 @@
 // guest/src/mqtt/mod.rs
 // mqtt payload building module.
-// transforms parsed modbus data into json payloads for publishing to mqtt topics.
+// transforms parsed modbus data into json payloads for publishing to mqtt topics,
+// or into sparkplug b protobuf payloads for scada hosts that expect them.
 
 pub mod payload;
+pub mod protobuf;
+pub mod sparkplug;
+pub mod topic;
+
+use sparkplug::EdgeNode;
+
+/// how telemetry is encoded on the sink
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TelemetryFormat {
+    #[default]
+    Json,                     // ics/telemetry/unit_{id}, one json document per response
+    SparkplugB(EdgeNode),     // spBv1.0 topics, births, deaths and protobuf metrics
+}
+
+/// what the gateway adds to every message it publishes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PublishSettings {
+    pub gateway_id: String,                  // user property, left out when empty
+    pub telemetry_expiry_secs: Option<u32>,  // message expiry on telemetry
+}
@@ -4,6 +4,13 @@
 // consumption by scada historians and cloud analytics platforms.
 
 use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::modbus::datatype::Value;
+
+/// version of the json payload schemas, sent as a user property.
+/// bump it when a field is renamed, removed or changes meaning.
+pub const SCHEMA_VERSION: &str = "1";
 
 /// telemetry payload published to mqtt
 /// this is the json structure that downstream systems will receive
@@ -11,18 +18,73 @@ use serde::Serialize;
 pub struct TelemetryPayload {
     pub source: String,           // e.g., "modbus://10.0.0.50:502"
     pub unit_id: u8,              // modbus slave address
-    pub function: String,         // "read_holding_registers" or "read_input_registers"
-    pub registers: Vec<Register>, // parsed register values
-    pub timestamp: String,        // iso 8601 format
+    pub function: String,         // e.g., "read_holding_registers" or "read_coils"
+    pub correlated: bool,         // false if no request was seen - addresses are offsets from 0
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub may_be_padded: bool,      // uncorrelated bits - up to 7 trailing "false" bits may be byte padding
+    #[serde(flatten)]
+    pub data: TelemetryData,      // "registers" or "bits", depending on function
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub status_bits: Vec<StatusBit>, // named bits of status words in this response
+    pub received_at: String,      // rfc 3339 - when the host received the frame
+    pub timestamp: String,        // rfc 3339 - when the gateway published
+}
+
+/// point values carried by a telemetry payload
+/// serialized as a "registers" or "bits" array on the payload itself
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryData {
+    Registers(Vec<Register>),     // 0x03 / 0x04 - 16-bit words
+    Bits(Vec<Bit>),               // 0x01 / 0x02 - coils and discrete inputs
 }
 
-/// individual register value with optional human-readable label
+/// individual register value with optional tag from the register map.
+/// a typed tag folds the registers it spans into one entry at its first address.
 #[derive(Serialize, Debug)]
 pub struct Register {
     pub address: u16,             // register address (0-65535)
-    pub value: u16,               // raw 16-bit value
+    pub value: Value,             // decoded and scaled per the tag, raw 16-bit otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<&'static str>, // e.g. "float32" - absent for raw registers
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub label: Option<String>,    // human-readable name if configured
+    pub label: Option<String>,    // tag name, e.g. "boiler_temp"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,     // engineering unit of value, e.g. "degC"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<Vec<u16>>,    // registers as received, when the map asks for audit
+}
+
+/// individual coil or discrete input state with optional tag from the register map
+#[derive(Serialize, Debug)]
+pub struct Bit {
+    pub address: u16,             // coil / input address (0-65535)
+    pub value: bool,              // on = true, off = false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,    // tag name, e.g. "pump_running"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// one named bit of a status word, from the register map
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StatusBit {
+    pub address: u16,             // register holding the status word
+    pub bit: u8,                  // 0 = least significant
+    pub label: String,            // e.g. "pump_running"
+    pub value: bool,
+}
+
+impl TelemetryData {
+    /// true when every point was dropped by the register map
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Registers(registers) => registers.is_empty(),
+            Self::Bits(bits) => bits.is_empty(),
+        }
+    }
 }
 
 impl TelemetryPayload {
@@ -32,6 +94,185 @@ impl TelemetryPayload {
     }
 }
 
+/// event published when a named status bit flips
+/// one event per bit, so subscribers never diff status words themselves
+#[derive(Serialize, Debug)]
+pub struct BitChangePayload {
+    pub source: String,           // e.g., "modbus://10.0.0.50:502"
+    pub unit_id: u8,              // modbus slave address
+    pub event: &'static str,      // always "bit_change"
+    #[serde(flatten)]
+    pub bit: StatusBit,           // the bit and its new value
+    pub received_at: String,      // rfc 3339 - when the host received the frame
+    pub timestamp: String,        // rfc 3339 - when the gateway published
+}
+
+impl BitChangePayload {
+    /// serialize to json string for mqtt publishing
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".into())
+    }
+}
+
+/// one limit alarm on a tagged register
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Alarm {
+    pub address: u16,
+    pub tag: String,              // e.g. "boiler_temp"
+    pub level: &'static str,      // "hihi", "hi", "lo" or "lolo"
+    pub severity: &'static str,   // "critical" or "warning"
+    pub value: f64,               // scaled value that raised or cleared it
+    pub limit: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+/// event published when a limit alarm is raised or clears
+#[derive(Serialize, Debug)]
+pub struct AlarmPayload {
+    pub source: String,           // e.g., "modbus://10.0.0.50:502"
+    pub unit_id: u8,              // modbus slave address
+    pub event: &'static str,      // "alarm_raised" or "alarm_cleared"
+    #[serde(flatten)]
+    pub alarm: Alarm,
+    pub received_at: String,      // rfc 3339 - when the host received the frame
+    pub timestamp: String,        // rfc 3339 - when the gateway published
+}
+
+impl AlarmPayload {
+    /// serialize to json string for mqtt publishing
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".into())
+    }
+}
+
+/// one suspected process-value injection on a tagged register
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub address: u16,
+    pub tag: String,              // e.g. "tank_level"
+    pub kind: &'static str,       // "rate_of_change", "frozen_value" or "outlier"
+    pub value: f64,               // scaled value that failed the check
+    pub observed: f64,            // units/s, ms unchanged or sigmas, per kind
+    pub threshold: f64,           // configured bound, in the same units
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+/// security event published when a value fails an anomaly check
+/// kept off the alarm topic - the soc acts on these, not the operators
+#[derive(Serialize, Debug)]
+pub struct AnomalyPayload {
+    pub source: String,           // e.g., "modbus://10.0.0.50:502"
+    pub unit_id: u8,              // modbus slave address
+    pub event: &'static str,      // always "security_anomaly"
+    #[serde(flatten)]
+    pub anomaly: Anomaly,
+    pub received_at: String,      // rfc 3339 - when the host received the frame
+    pub timestamp: String,        // rfc 3339 - when the gateway published
+}
+
+impl AnomalyPayload {
+    /// serialize to json string for mqtt publishing
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".into())
+    }
+}
+
+/// isa-18.2 state of one alarm
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AlarmState {
+    pub unit_id: u8,              // modbus slave address
+    pub address: u16,
+    pub tag: String,              // e.g. "boiler_temp"
+    pub level: &'static str,      // "hihi", "hi", "lo" or "lolo"
+    pub severity: &'static str,   // "critical" or "warning"
+    pub limit: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub state: &'static str,      // "normal", "unacknowledged", "acknowledged",
+                                  // "returned_unacknowledged", "shelved" or "suppressed"
+    pub active: bool,             // the value is past the limit
+    pub acknowledged: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,       // value at the last raise or clear
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shelved_until_ms: Option<u64>, // unix milliseconds
+}
+
+/// alarm state document published retained per alarm
+/// so a reconnecting hmi sees every alarm's current state at once
+#[derive(Serialize, Debug)]
+pub struct AlarmStatePayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,   // absent when an operator command changed the state
+    #[serde(flatten)]
+    pub alarm: AlarmState,
+    pub timestamp: String,        // rfc 3339 - when the gateway published
+}
+
+impl AlarmStatePayload {
+    /// serialize to json string for mqtt publishing
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".into())
+    }
+}
+
+/// exception payload published to the status topic
+/// lets operators see a misconfigured poll (e.g. illegal data address)
+/// separately from telemetry and from rejected attack frames
+#[derive(Serialize, Debug)]
+pub struct ExceptionPayload {
+    pub source: String,           // e.g., "modbus://10.0.0.50:502"
+    pub unit_id: u8,              // modbus slave address
+    pub function: String,         // function the failed request used
+    pub exception_code: u8,       // raw exception code byte
+    pub exception: String,        // e.g., "illegal_data_address"
+    pub received_at: String,      // rfc 3339 - when the host received the frame
+    pub timestamp: String,        // rfc 3339 - when the gateway published
+}
+
+impl ExceptionPayload {
+    /// serialize to json string for mqtt publishing
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".into())
+    }
+}
+
+/// device identity document published retained per unit id
+/// so an asset inventory sees every plc's identity as soon as it subscribes
+#[derive(Serialize, Debug)]
+pub struct DeviceIdentityPayload {
+    pub source: String,           // e.g., "modbus://10.0.0.50:502"
+    pub unit_id: u8,              // modbus slave address
+    pub conformity_level: u8,     // identification categories the device supports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_application_name: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub extended: BTreeMap<String, String>, // vendor-private objects, keyed "0x80".."0xFF"
+    pub received_at: String,      // rfc 3339 - when the host received the frame
+    pub timestamp: String,        // rfc 3339 - when the gateway published
+}
+
+impl DeviceIdentityPayload {
+    /// serialize to json string for mqtt publishing
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,16 +283,219 @@ mod tests {
             source: "modbus://10.0.0.50:502".to_string(),
             unit_id: 1,
             function: "read_holding_registers".to_string(),
-            registers: vec![
-                Register { address: 0, value: 1000, label: Some("temperature".to_string()) },
-                Register { address: 1, value: 2000, label: None },
-            ],
-            timestamp: "2026-01-05T00:00:00Z".to_string(),
+            correlated: true,
+            may_be_padded: false,
+            data: TelemetryData::Registers(vec![
+                Register {
+                    address: 0,
+                    value: Value::Float(21.5),
+                    data_type: Some("float32"),
+                    label: Some("temperature".to_string()),
+                    description: None,
+                    unit: Some("degC".to_string()),
+                    raw: Some(vec![0x41AC, 0x0000]),
+                },
+                Register {
+                    address: 2,
+                    value: Value::Unsigned(2000),
+                    data_type: None,
+                    label: None,
+                    description: None,
+                    unit: None,
+                    raw: None,
+                },
+            ]),
+            status_bits: Vec::new(),
+            received_at: "2026-01-05T00:00:00.000Z".to_string(),
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
         };
         
         let json = payload.to_json();
         assert!(json.contains("modbus://10.0.0.50:502"));
-        assert!(json.contains("temperature"));
-        assert!(json.contains("1000"));
+        assert!(!json.contains("status_bits"));
+        assert!(json.contains("\"value\":21.5,\"data_type\":\"float32\",\"label\":\"temperature\",\"unit\":\"degC\""));
+        assert!(json.contains("\"raw\":[16812,0]"));
+        assert!(json.contains("{\"address\":2,\"value\":2000}"));
+        // acquisition time and publish time side by side
+        assert!(json.contains("\"received_at\":\"2026-01-05T00:00:00.000Z\",\"timestamp\":\"2026-01-05T00:00:00.125Z\""));
+    }
+
+    #[test]
+    fn test_bits_payload_serialization() {
+        let mut payload = TelemetryPayload {
+            source: "modbus://10.0.0.50:502".to_string(),
+            unit_id: 1,
+            function: "read_coils".to_string(),
+            correlated: true,
+            may_be_padded: false,
+            data: TelemetryData::Bits(vec![
+                Bit { address: 0, value: true, label: None, description: None },
+                Bit { address: 1, value: false, label: None, description: None },
+            ]),
+            status_bits: Vec::new(),
+            received_at: "2026-01-05T00:00:00.000Z".to_string(),
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
+        };
+
+        let json = payload.to_json();
+        assert!(json.contains("\"bits\":[{\"address\":0,\"value\":true}"));
+        assert!(!json.contains("registers"));
+        assert!(!json.contains("may_be_padded"));
+
+        // without the request the quantity - and so the padding - is unknown
+        payload.correlated = false;
+        payload.may_be_padded = true;
+        assert!(payload.to_json().contains("\"correlated\":false,\"may_be_padded\":true"));
+    }
+
+    #[test]
+    fn test_status_bits_serialization() {
+        let bit = StatusBit { address: 40, bit: 3, label: "pump_running".to_string(), value: true };
+        let payload = TelemetryPayload {
+            source: "modbus://10.0.0.50:502".to_string(),
+            unit_id: 1,
+            function: "read_holding_registers".to_string(),
+            correlated: true,
+            may_be_padded: false,
+            data: TelemetryData::Registers(Vec::new()),
+            status_bits: vec![bit.clone()],
+            received_at: "2026-01-05T00:00:00.000Z".to_string(),
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
+        };
+        let json = payload.to_json();
+        assert!(json.contains("\"status_bits\":[{\"address\":40,\"bit\":3,\"label\":\"pump_running\",\"value\":true}]"));
+
+        let event = BitChangePayload {
+            source: "modbus://10.0.0.50:502".to_string(),
+            unit_id: 1,
+            event: "bit_change",
+            bit,
+            received_at: "2026-01-05T00:00:00.000Z".to_string(),
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
+        };
+        let json = event.to_json();
+        assert!(json.contains("\"event\":\"bit_change\",\"address\":40,\"bit\":3"));
+    }
+
+    #[test]
+    fn test_alarm_payload_serialization() {
+        let payload = AlarmPayload {
+            source: "modbus://10.0.0.50:502".to_string(),
+            unit_id: 1,
+            event: "alarm_raised",
+            alarm: Alarm {
+                address: 100,
+                tag: "boiler_temp".to_string(),
+                level: "hihi",
+                severity: "critical",
+                value: 121.5,
+                limit: 120.0,
+                unit: Some("degC".to_string()),
+            },
+            received_at: "2026-01-05T00:00:00.000Z".to_string(),
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
+        };
+
+        let json = payload.to_json();
+        assert!(json.contains("\"event\":\"alarm_raised\",\"address\":100,\"tag\":\"boiler_temp\""));
+        assert!(json.contains("\"level\":\"hihi\",\"severity\":\"critical\",\"value\":121.5,\"limit\":120.0"));
+        assert!(json.contains("\"unit\":\"degC\""));
+    }
+
+    #[test]
+    fn test_alarm_state_serialization() {
+        let mut payload = AlarmStatePayload {
+            source: Some("modbus://10.0.0.50:502".to_string()),
+            alarm: AlarmState {
+                unit_id: 1,
+                address: 100,
+                tag: "boiler_temp".to_string(),
+                level: "hi",
+                severity: "warning",
+                limit: 100.0,
+                unit: None,
+                state: "shelved",
+                active: true,
+                acknowledged: false,
+                value: Some(104.5),
+                shelved_until_ms: Some(1767571200000),
+            },
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
+        };
+
+        let json = payload.to_json();
+        assert!(json.contains("\"unit_id\":1,\"address\":100"));
+        assert!(json.contains("\"state\":\"shelved\",\"active\":true,\"acknowledged\":false,\"value\":104.5"));
+        assert!(json.contains("\"shelved_until_ms\":1767571200000"));
+        assert!(!json.contains("\"unit\":"));
+
+        payload.source = None;
+        assert!(!payload.to_json().contains("\"source\""));
+    }
+
+    #[test]
+    fn test_anomaly_payload_serialization() {
+        let payload = AnomalyPayload {
+            source: "modbus://10.0.0.50:502".to_string(),
+            unit_id: 1,
+            event: "security_anomaly",
+            anomaly: Anomaly {
+                address: 10,
+                tag: "tank_level".to_string(),
+                kind: "rate_of_change",
+                value: 6.0,
+                observed: 2.0,
+                threshold: 0.5,
+                unit: Some("m".to_string()),
+            },
+            received_at: "2026-01-05T00:00:00.000Z".to_string(),
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
+        };
+
+        let json = payload.to_json();
+        assert!(json.contains("\"event\":\"security_anomaly\",\"address\":10"));
+        assert!(json.contains("\"kind\":\"rate_of_change\",\"value\":6.0,\"observed\":2.0,\"threshold\":0.5"));
+    }
+
+    #[test]
+    fn test_exception_payload_serialization() {
+        let payload = ExceptionPayload {
+            source: "modbus://10.0.0.50:502".to_string(),
+            unit_id: 1,
+            function: "read_holding_registers".to_string(),
+            exception_code: 0x02,
+            exception: "illegal_data_address".to_string(),
+            received_at: "2026-01-05T00:00:00.000Z".to_string(),
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
+        };
+
+        let json = payload.to_json();
+        assert!(json.contains("\"exception_code\":2"));
+        assert!(json.contains("illegal_data_address"));
+    }
+
+    #[test]
+    fn test_device_identity_serialization() {
+        let payload = DeviceIdentityPayload {
+            source: "modbus://10.0.0.50:502".to_string(),
+            unit_id: 3,
+            conformity_level: 0x81,
+            vendor_name: Some("Acme".to_string()),
+            product_code: Some("X-20".to_string()),
+            revision: Some("v1.2".to_string()),
+            vendor_url: None,
+            product_name: None,
+            model_name: None,
+            user_application_name: None,
+            extended: BTreeMap::new(),
+            received_at: "2026-01-05T00:00:00.000Z".to_string(),
+            timestamp: "2026-01-05T00:00:00.125Z".to_string(),
+        };
+
+        let json = payload.to_json();
+        assert!(json.contains("\"vendor_name\":\"Acme\""));
+        assert!(json.contains("\"revision\":\"v1.2\""));
+        assert!(!json.contains("vendor_url"));
+        assert!(!json.contains("extended"));
     }
 }
@@ -0,0 +1,112 @@
+// guest/src/mqtt/protobuf.rs
+// minimal protocol buffers wire-format writer.
+// sparkplug b only needs a handful of scalar fields and nested messages, so
+// a writer this small replaces a code generator and its build-time protoc.
+// fields are written in the order called; optional fields are simply omitted.
+
+/// wire types used by the fields we write
+const VARINT: u8 = 0;
+const FIXED64: u8 = 1;
+const LENGTH_DELIMITED: u8 = 2;
+const FIXED32: u8 = 5;
+
+/// appends fields of one message to a buffer
+#[derive(Debug, Default)]
+pub struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the encoded message
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    /// uint32, uint64 and enum fields
+    pub fn varint(&mut self, field: u32, value: u64) {
+        self.key(field, VARINT);
+        self.raw_varint(value);
+    }
+
+    pub fn bool(&mut self, field: u32, value: bool) {
+        self.varint(field, value as u64);
+    }
+
+    pub fn float(&mut self, field: u32, value: f32) {
+        self.key(field, FIXED32);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn double(&mut self, field: u32, value: f64) {
+        self.key(field, FIXED64);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// bytes fields and embedded messages
+    pub fn bytes(&mut self, field: u32, value: &[u8]) {
+        self.key(field, LENGTH_DELIMITED);
+        self.raw_varint(value.len() as u64);
+        self.buf.extend_from_slice(value);
+    }
+
+    pub fn string(&mut self, field: u32, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.raw_varint(((field as u64) << 3) | wire_type as u64);
+    }
+
+    /// little-endian base 128, seven bits per byte
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8 & 0x7F) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(write: impl FnOnce(&mut Writer)) -> Vec<u8> {
+        let mut writer = Writer::new();
+        write(&mut writer);
+        writer.finish()
+    }
+
+    #[test]
+    fn test_varint() {
+        // the protobuf documentation's own example
+        assert_eq!(encode(|w| w.varint(1, 150)), vec![0x08, 0x96, 0x01]);
+        assert_eq!(encode(|w| w.varint(3, 0)), vec![0x18, 0x00]);
+        assert_eq!(encode(|w| w.bool(14, true)), vec![0x70, 0x01]);
+        // ten bytes for the top bit of a u64
+        assert_eq!(encode(|w| w.varint(1, u64::MAX)).len(), 11);
+        // field numbers past 15 take a two-byte key
+        assert_eq!(encode(|w| w.varint(16, 1)), vec![0x80, 0x01, 0x01]);
+    }
+
+    #[test]
+    fn test_fixed() {
+        assert_eq!(encode(|w| w.float(12, 1.0)), vec![0x65, 0x00, 0x00, 0x80, 0x3F]);
+        assert_eq!(
+            encode(|w| w.double(13, 1.0)),
+            vec![0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF0, 0x3F]
+        );
+    }
+
+    #[test]
+    fn test_length_delimited() {
+        assert_eq!(encode(|w| w.string(2, "testing")), b"\x12\x07testing".to_vec());
+        let inner = encode(|w| w.varint(1, 150));
+        assert_eq!(encode(|w| w.bytes(3, &inner)), vec![0x1A, 0x03, 0x08, 0x96, 0x01]);
+        assert_eq!(encode(|w| w.bytes(5, &[])), vec![0x2A, 0x00]);
+    }
+}
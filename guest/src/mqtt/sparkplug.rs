@@ -0,0 +1,712 @@
+// guest/src/mqtt/sparkplug.rs
+// sparkplug b encoding of telemetry, for scada platforms such as ignition.
+// the gateway is one edge node and each modbus unit id is a device named
+// "unit_{id}". the node is born (NBIRTH) right after its death certificate
+// (NDEATH) is registered as the mqtt will, each device is born (DBIRTH) the
+// first time it reports, and later reports are DDATA. a device that stops
+// answering polls dies (DDEATH) and is born again when it next reports.
+// only tagged points are published: a birth certificate must declare every
+// metric the device will ever send, and untagged points have no stable name.
+
+use std::collections::BTreeMap;
+
+use super::payload::{StatusBit, TelemetryData};
+use super::protobuf::Writer;
+use crate::modbus::datatype::{DataType, Value};
+use crate::register_map::{PointKey, RegisterMap, Table, Tag};
+
+/// topic namespace of every sparkplug b message
+const NAMESPACE: &str = "spBv1.0";
+
+/// where the gateway sits in the sparkplug namespace
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeNode {
+    pub group_id: String,
+    pub node_id: String,
+}
+
+impl EdgeNode {
+    /// ids are topic levels, so they must be non-empty and free of
+    /// separators and wildcards
+    pub fn validate(&self) -> Result<(), String> {
+        for (what, id) in [("group id", &self.group_id), ("edge node id", &self.node_id)] {
+            if id.is_empty() || id.contains(['/', '+', '#']) {
+                return Err(format!("sparkplug {} '{}' must be a single topic level", what, id));
+            }
+        }
+        Ok(())
+    }
+
+    /// spBv1.0/{group}/{type}/{node}, plus /unit_{id} for device messages
+    fn topic(&self, message_type: &str, unit_id: Option<u8>) -> String {
+        let node = format!("{}/{}/{}/{}", NAMESPACE, self.group_id, message_type, self.node_id);
+        match unit_id {
+            Some(unit_id) => format!("{}/unit_{}", node, unit_id),
+            None => node,
+        }
+    }
+}
+
+/// the sparkplug b data types we publish
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricType {
+    Int16 = 2,
+    Int32 = 3,
+    Int64 = 4,
+    UInt16 = 6,
+    UInt32 = 7,
+    UInt64 = 8,
+    Float = 9,
+    Double = 10,
+    Boolean = 11,
+    String = 12,
+}
+
+impl MetricType {
+    /// the type a tag publishes as - scaled values are always doubles
+    fn of(table: Table, tag: &Tag) -> Self {
+        if matches!(table, Table::Coil | Table::DiscreteInput) {
+            return Self::Boolean;
+        }
+        if tag.scaling.is_some() {
+            return Self::Double;
+        }
+        match tag.data_type {
+            DataType::Uint16 | DataType::Bcd16 => Self::UInt16,
+            DataType::Int16 => Self::Int16,
+            DataType::Uint32 | DataType::Bcd32 => Self::UInt32,
+            DataType::Int32 => Self::Int32,
+            DataType::Uint64 => Self::UInt64,
+            DataType::Int64 => Self::Int64,
+            DataType::Float32 => Self::Float,
+            DataType::Float64 => Self::Double,
+            DataType::Ascii(_) => Self::String,
+        }
+    }
+}
+
+/// a metric value, in the payload field its type calls for
+#[derive(Debug, Clone, PartialEq)]
+enum MetricValue {
+    Int(u32),   // 8-32 bit integers - signed ones as two's complement
+    Long(u64),  // 64 bit integers - signed ones as two's complement
+    Float(f32),
+    Double(f64),
+    Boolean(bool),
+    String(String),
+}
+
+impl MetricValue {
+    /// `value` encoded as `metric_type` - None if the two disagree
+    fn new(metric_type: MetricType, value: &Value) -> Option<Self> {
+        Some(match (metric_type, value) {
+            (MetricType::Int16 | MetricType::Int32, &Value::Signed(v)) => Self::Int(v as i32 as u32),
+            (MetricType::UInt16 | MetricType::UInt32, &Value::Unsigned(v)) => Self::Int(v as u32),
+            (MetricType::Int64, &Value::Signed(v)) => Self::Long(v as u64),
+            (MetricType::UInt64, &Value::Unsigned(v)) => Self::Long(v),
+            (MetricType::Float, value) => Self::Float(value.as_f64()? as f32),
+            (MetricType::Double, value) => Self::Double(value.as_f64()?),
+            (MetricType::String, Value::Text(text)) => Self::String(text.clone()),
+            _ => return None,
+        })
+    }
+}
+
+/// one named value - without a value it is published as null
+#[derive(Debug, Clone, PartialEq)]
+struct Metric {
+    name: String,
+    metric_type: MetricType,
+    timestamp_ms: Option<u64>,  // when the value was received - none for node metrics
+    value: Option<MetricValue>,
+}
+
+impl Metric {
+    fn encode(&self) -> Vec<u8> {
+        let mut w = Writer::new();
+        w.string(1, &self.name);
+        if let Some(timestamp_ms) = self.timestamp_ms {
+            w.varint(3, timestamp_ms);
+        }
+        w.varint(4, self.metric_type as u64);
+        match &self.value {
+            None => w.bool(7, true),
+            Some(MetricValue::Int(v)) => w.varint(10, *v as u64),
+            Some(MetricValue::Long(v)) => w.varint(11, *v),
+            Some(MetricValue::Float(v)) => w.float(12, *v),
+            Some(MetricValue::Double(v)) => w.double(13, *v),
+            Some(MetricValue::Boolean(v)) => w.bool(14, *v),
+            Some(MetricValue::String(v)) => w.string(15, v),
+        }
+        w.finish()
+    }
+}
+
+/// encode a payload - only NDEATH goes without a sequence number
+fn encode(timestamp_ms: u64, metrics: &[Metric], seq: Option<u8>) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.varint(1, timestamp_ms);
+    for metric in metrics {
+        w.bytes(2, &metric.encode());
+    }
+    if let Some(seq) = seq {
+        w.varint(3, seq as u64);
+    }
+    w.finish()
+}
+
+/// one message for the host to hand to the broker
+#[derive(Debug, Clone, PartialEq)]
+pub enum Output {
+    Will { topic: String, payload: Vec<u8> },     // register as the mqtt will
+    Publish { topic: String, payload: Vec<u8> },  // qos 0, never retained
+}
+
+/// edge node state: the birth/death and message sequence numbers, and the
+/// last value of every metric of every born device, for rebirths.
+/// bounded by the register map.
+#[derive(Debug, Default)]
+pub struct Session {
+    bd_seq: u8,  // one per will registered
+    seq: u8,     // 0 at NBIRTH, then one per message
+    node_born: bool,
+    devices: BTreeMap<u8, BTreeMap<String, Metric>>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the messages for one response's tagged points: the node's will and
+    /// birth the first time anything reports, the device's birth the first
+    /// time it reports (carrying these values), and DDATA after that.
+    /// metrics are stamped with the response's receive time, payloads with now.
+    #[allow(clippy::too_many_arguments)]
+    pub fn data(
+        &mut self,
+        node: &EdgeNode,
+        map: &RegisterMap,
+        unit_id: u8,
+        table: Table,
+        data: &TelemetryData,
+        status_bits: &[StatusBit],
+        received_ms: u64,
+        now_ms: u64,
+    ) -> Vec<Output> {
+        let metrics = metrics(map, unit_id, table, data, status_bits, received_ms);
+        if metrics.is_empty() {
+            return Vec::new();
+        }
+
+        let mut outputs = Vec::new();
+        if !self.node_born {
+            outputs.extend(self.node_birth(node, now_ms, true));
+        }
+        let born = self.devices.contains_key(&unit_id);
+        let last = self.devices.entry(unit_id).or_default();
+        for metric in &metrics {
+            last.insert(metric.name.clone(), metric.clone());
+        }
+        if born {
+            let seq = self.next_seq();
+            outputs.push(Output::Publish {
+                topic: node.topic("DDATA", Some(unit_id)),
+                payload: encode(now_ms, &metrics, Some(seq)),
+            });
+        } else {
+            outputs.push(self.device_birth(node, map, unit_id, now_ms));
+        }
+        outputs
+    }
+
+    /// DDEATH for a device that stopped answering, if it was born
+    pub fn device_death(&mut self, node: &EdgeNode, unit_id: u8, now_ms: u64) -> Option<Output> {
+        self.devices.remove(&unit_id)?;
+        let seq = self.next_seq();
+        Some(Output::Publish {
+            topic: node.topic("DDEATH", Some(unit_id)),
+            payload: encode(now_ms, &[], Some(seq)),
+        })
+    }
+
+    /// answer a host application's rebirth request: NBIRTH again in the
+    /// same session, then DBIRTH for every live device with its last values.
+    /// a node that was never born has nothing to repeat.
+    pub fn rebirth(&mut self, node: &EdgeNode, map: &RegisterMap, now_ms: u64) -> Vec<Output> {
+        if !self.node_born {
+            return Vec::new();
+        }
+        let mut outputs = self.node_birth(node, now_ms, false);
+        let units: Vec<u8> = self.devices.keys().copied().collect();
+        for unit_id in units {
+            outputs.push(self.device_birth(node, map, unit_id, now_ms));
+        }
+        outputs
+    }
+
+    /// NBIRTH, preceded by a new NDEATH will when starting a session.
+    /// both carry the same bdSeq so the host can pair them.
+    fn node_birth(&mut self, node: &EdgeNode, now_ms: u64, new_session: bool) -> Vec<Output> {
+        let mut outputs = Vec::new();
+        if new_session {
+            self.bd_seq = if self.node_born { self.bd_seq.wrapping_add(1) } else { self.bd_seq };
+            outputs.push(Output::Will {
+                topic: node.topic("NDEATH", None),
+                payload: encode(now_ms, &[self.bd_seq_metric()], None),
+            });
+        }
+        self.node_born = true;
+        self.seq = 0;
+        let rebirth = Metric {
+            name: "Node Control/Rebirth".to_string(),
+            metric_type: MetricType::Boolean,
+            timestamp_ms: None,
+            value: Some(MetricValue::Boolean(false)),
+        };
+        let seq = self.next_seq();
+        outputs.push(Output::Publish {
+            topic: node.topic("NBIRTH", None),
+            payload: encode(now_ms, &[self.bd_seq_metric(), rebirth], Some(seq)),
+        });
+        outputs
+    }
+
+    /// DBIRTH declaring every tagged point of the device, with the last
+    /// value seen for each - null until one arrives
+    fn device_birth(&mut self, node: &EdgeNode, map: &RegisterMap, unit_id: u8, now_ms: u64) -> Output {
+        let last = self.devices.get(&unit_id);
+        let metrics: Vec<Metric> = declared(map, unit_id)
+            .into_iter()
+            .map(|(name, metric_type)| {
+                match last.and_then(|l| l.get(&name)) {
+                    Some(metric) => Metric { metric_type, ..metric.clone() },
+                    None => Metric { name, metric_type, timestamp_ms: None, value: None },
+                }
+            })
+            .collect();
+        let seq = self.next_seq();
+        Output::Publish {
+            topic: node.topic("DBIRTH", Some(unit_id)),
+            payload: encode(now_ms, &metrics, Some(seq)),
+        }
+    }
+
+    fn bd_seq_metric(&self) -> Metric {
+        Metric {
+            name: "bdSeq".to_string(),
+            metric_type: MetricType::UInt64,
+            timestamp_ms: None,
+            value: Some(MetricValue::Long(self.bd_seq as u64)),
+        }
+    }
+
+    /// sequence number for the next message, wrapping after 255
+    fn next_seq(&mut self) -> u8 {
+        let seq = self.seq;
+        self.seq = self.seq.wrapping_add(1);
+        seq
+    }
+}
+
+/// every metric a device can send: one per tag, except status words that
+/// publish only their bits, plus one per named status bit
+fn declared(map: &RegisterMap, unit_id: u8) -> Vec<(String, MetricType)> {
+    let mut declared = Vec::new();
+    for (key, tag) in map.unit_tags(unit_id) {
+        let status = tag.status_bits.as_ref();
+        if !status.is_some_and(|s| s.replace_word) {
+            declared.push((tag.name.clone(), MetricType::of(key.table, tag)));
+        }
+        for field in status.iter().flat_map(|s| &s.fields) {
+            declared.push((format!("{}/{}", tag.name, field.name), MetricType::Boolean));
+        }
+    }
+    declared
+}
+
+/// metrics for the tagged points of one response
+fn metrics(
+    map: &RegisterMap,
+    unit_id: u8,
+    table: Table,
+    data: &TelemetryData,
+    status_bits: &[StatusBit],
+    received_ms: u64,
+) -> Vec<Metric> {
+    let tag = |address: u16| map.get(&PointKey { unit_id, table, address });
+    let mut metrics = Vec::new();
+    match data {
+        TelemetryData::Registers(registers) => {
+            // unlabelled registers have no tag, or no real address
+            for register in registers.iter().filter(|r| r.label.is_some()) {
+                let Some(tag) = tag(register.address) else { continue };
+                let metric_type = MetricType::of(table, tag);
+                metrics.push(Metric {
+                    name: tag.name.clone(),
+                    metric_type,
+                    timestamp_ms: Some(received_ms),
+                    value: MetricValue::new(metric_type, &register.value),
+                });
+            }
+        }
+        TelemetryData::Bits(bits) => {
+            for bit in bits.iter().filter(|b| b.label.is_some()) {
+                let Some(tag) = tag(bit.address) else { continue };
+                metrics.push(Metric {
+                    name: tag.name.clone(),
+                    metric_type: MetricType::Boolean,
+                    timestamp_ms: Some(received_ms),
+                    value: Some(MetricValue::Boolean(bit.value)),
+                });
+            }
+        }
+    }
+    for bit in status_bits {
+        let Some(tag) = tag(bit.address) else { continue };
+        metrics.push(Metric {
+            name: format!("{}/{}", tag.name, bit.label),
+            metric_type: MetricType::Boolean,
+            timestamp_ms: Some(received_ms),
+            value: Some(MetricValue::Boolean(bit.value)),
+        });
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt::payload::{Bit, Register};
+    use crate::register_map::{Scaling, Unmapped};
+    use crate::status_bits::{BitField, StatusBits};
+
+    /// a decoded protobuf field: varints and fixed-width as numbers,
+    /// length-delimited as bytes
+    #[derive(Debug, Clone, PartialEq)]
+    enum Field {
+        Number(u64),
+        Bytes(Vec<u8>),
+    }
+
+    /// just enough of a protobuf reader to check what the writer produced
+    fn fields(mut buf: &[u8]) -> Vec<(u32, Field)> {
+        fn varint(buf: &mut &[u8]) -> u64 {
+            let mut value = 0;
+            for shift in (0..64).step_by(7) {
+                let byte = buf[0];
+                *buf = &buf[1..];
+                value |= ((byte & 0x7F) as u64) << shift;
+                if byte < 0x80 {
+                    break;
+                }
+            }
+            value
+        }
+        let mut fields = Vec::new();
+        while !buf.is_empty() {
+            let key = varint(&mut buf);
+            let field = match key & 7 {
+                0 => Field::Number(varint(&mut buf)),
+                1 | 5 => {
+                    let width = if key & 7 == 1 { 8 } else { 4 };
+                    let mut bytes = [0; 8];
+                    bytes[..width].copy_from_slice(&buf[..width]);
+                    buf = &buf[width..];
+                    Field::Number(u64::from_le_bytes(bytes))
+                }
+                _ => {
+                    let len = varint(&mut buf) as usize;
+                    let bytes = buf[..len].to_vec();
+                    buf = &buf[len..];
+                    Field::Bytes(bytes)
+                }
+            };
+            fields.push(((key >> 3) as u32, field));
+        }
+        fields
+    }
+
+    /// the fields we write, copied from eclipse tahu's sparkplug_b.proto.
+    /// checked against here rather than against our own reader, which would
+    /// agree with any field number the writer used.
+    const PAYLOAD_PROTO: &str = "
+        optional uint64 timestamp = 1;
+        repeated Metric metrics = 2;
+        optional uint64 seq = 3;
+    ";
+    const METRIC_PROTO: &str = "
+        optional string name = 1;
+        optional uint64 alias = 2;
+        optional uint64 timestamp = 3;
+        optional uint32 datatype = 4;
+        optional bool is_historical = 5;
+        optional bool is_transient = 6;
+        optional bool is_null = 7;
+        uint32 int_value = 10;
+        uint64 long_value = 11;
+        float float_value = 12;
+        double double_value = 13;
+        bool boolean_value = 14;
+        string string_value = 15;
+    ";
+
+    /// the number of a named field in a proto excerpt
+    fn proto_field(proto: &str, name: &str) -> u32 {
+        let pattern = format!(" {} = ", name);
+        let line = proto.lines().find(|line| line.contains(&pattern)).unwrap();
+        line.split(" = ").nth(1).unwrap().trim_end_matches(';').parse().unwrap()
+    }
+
+    /// the payload's seq, if present
+    fn seq(payload: &[u8]) -> Option<u64> {
+        fields(payload).into_iter().find_map(|(field, value)| match (field, value) {
+            (3, Field::Number(seq)) => Some(seq),
+            _ => None,
+        })
+    }
+
+    /// name and every other field of each metric in a payload
+    fn metric_fields(payload: &[u8]) -> Vec<(String, Vec<(u32, Field)>)> {
+        fields(payload)
+            .into_iter()
+            .filter_map(|(field, value)| match (field, value) {
+                (2, Field::Bytes(metric)) => Some(fields(&metric)),
+                _ => None,
+            })
+            .map(|mut metric| match metric.remove(0) {
+                (1, Field::Bytes(name)) => (String::from_utf8(name).unwrap(), metric),
+                other => panic!("metric without a name first: {:?}", other),
+            })
+            .collect()
+    }
+
+    fn metric_names(payload: &[u8]) -> Vec<String> {
+        metric_fields(payload).into_iter().map(|(name, _)| name).collect()
+    }
+
+    fn node() -> EdgeNode {
+        EdgeNode {
+            group_id: "plant".to_string(),
+            node_id: "gateway1".to_string(),
+        }
+    }
+
+    fn key(table: Table, address: u16) -> PointKey {
+        PointKey { unit_id: 1, table, address }
+    }
+
+    /// unit 1: "level" (scaled) at holding register 0, a status word at 1
+    /// publishing only its bits, and "pump_on" at coil 4
+    fn map() -> RegisterMap {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+        let scaling = Scaling { gain: 0.1, offset: 0.0, min: None, max: None };
+        map.insert(key(Table::HoldingRegister, 0), Tag { scaling: Some(scaling), ..Tag::named("level") }).unwrap();
+        let status = StatusBits {
+            fields: vec![BitField { bit: 0, name: "running".to_string() }],
+            replace_word: true,
+        };
+        map.insert(key(Table::HoldingRegister, 1), Tag { status_bits: Some(status), ..Tag::named("status") }).unwrap();
+        map.insert(key(Table::Coil, 4), Tag::named("pump_on")).unwrap();
+        map
+    }
+
+    fn registers(level: f64) -> TelemetryData {
+        TelemetryData::Registers(vec![
+            Register {
+                address: 0,
+                value: Value::Float(level),
+                data_type: None,
+                label: Some("level".to_string()),
+                description: None,
+                unit: None,
+                raw: None,
+            },
+            // untagged - never published
+            Register {
+                address: 2,
+                value: Value::Unsigned(7),
+                data_type: None,
+                label: None,
+                description: None,
+                unit: None,
+                raw: None,
+            },
+        ])
+    }
+
+    fn running(value: bool) -> Vec<StatusBit> {
+        vec![StatusBit { address: 1, bit: 0, label: "running".to_string(), value }]
+    }
+
+    fn topics(outputs: &[Output]) -> Vec<&str> {
+        outputs
+            .iter()
+            .map(|o| match o {
+                Output::Will { topic, .. } | Output::Publish { topic, .. } => topic.as_str(),
+            })
+            .collect()
+    }
+
+    fn payload(output: &Output) -> &[u8] {
+        match output {
+            Output::Will { payload, .. } | Output::Publish { payload, .. } => payload,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(node().validate(), Ok(()));
+        assert!(EdgeNode { group_id: String::new(), ..node() }.validate().is_err());
+        assert!(EdgeNode { node_id: "a/b".to_string(), ..node() }.validate().is_err());
+        assert!(EdgeNode { group_id: "plant+".to_string(), ..node() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_metric_encoding() {
+        let metric = Metric {
+            name: "level".to_string(),
+            metric_type: MetricType::Int16,
+            timestamp_ms: None,
+            value: MetricValue::new(MetricType::Int16, &Value::Signed(-1)),
+        };
+        // name, datatype 2, int_value 0xFFFFFFFF
+        assert_eq!(
+            metric.encode(),
+            vec![0x0A, 0x05, b'l', b'e', b'v', b'e', b'l', 0x20, 0x02, 0x50, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F]
+        );
+
+        let null = Metric {
+            name: "x".to_string(),
+            metric_type: MetricType::Double,
+            timestamp_ms: Some(300),
+            value: None,
+        };
+        assert_eq!(null.encode(), vec![0x0A, 0x01, b'x', 0x18, 0xAC, 0x02, 0x20, 0x0A, 0x38, 0x01]);
+
+        assert_eq!(MetricValue::new(MetricType::UInt64, &Value::Signed(-1)), None);
+        assert_eq!(MetricValue::new(MetricType::Double, &Value::Unsigned(3)), Some(MetricValue::Double(3.0)));
+        assert_eq!(MetricValue::new(MetricType::Int64, &Value::Signed(-2)), Some(MetricValue::Long(u64::MAX - 1)));
+    }
+
+    #[test]
+    fn test_field_numbers_match_sparkplug_b_proto() {
+        let numbers = |payload: &[u8]| fields(payload).into_iter().map(|(field, _)| field).collect::<Vec<_>>();
+        let metric = |name| proto_field(METRIC_PROTO, name);
+
+        let timestamped = Metric {
+            name: "level".to_string(),
+            metric_type: MetricType::Double,
+            timestamp_ms: Some(900),
+            value: Some(MetricValue::Double(1.0)),
+        };
+        assert_eq!(
+            numbers(&timestamped.encode()),
+            vec![metric("name"), metric("timestamp"), metric("datatype"), metric("double_value")]
+        );
+        let null = Metric { timestamp_ms: None, value: None, ..timestamped.clone() };
+        assert_eq!(numbers(&null.encode()), vec![metric("name"), metric("datatype"), metric("is_null")]);
+        for (value, name) in [
+            (MetricValue::Int(1), "int_value"),
+            (MetricValue::Long(1), "long_value"),
+            (MetricValue::Float(1.0), "float_value"),
+            (MetricValue::Boolean(true), "boolean_value"),
+            (MetricValue::String("on".to_string()), "string_value"),
+        ] {
+            let encoded = Metric { value: Some(value), ..null.clone() }.encode();
+            assert_eq!(numbers(&encoded).last(), Some(&metric(name)));
+        }
+
+        let payload = encode(1000, &[null], Some(0));
+        let payload_field = |name| proto_field(PAYLOAD_PROTO, name);
+        assert_eq!(numbers(&payload), vec![payload_field("timestamp"), payload_field("metrics"), payload_field("seq")]);
+    }
+
+    #[test]
+    fn test_first_report_births_node_and_device() {
+        let mut session = Session::new();
+        let outputs = session.data(&node(), &map(), 1, Table::HoldingRegister, &registers(42.5), &running(true), 900, 1000);
+
+        assert_eq!(
+            topics(&outputs),
+            vec![
+                "spBv1.0/plant/NDEATH/gateway1",
+                "spBv1.0/plant/NBIRTH/gateway1",
+                "spBv1.0/plant/DBIRTH/gateway1/unit_1",
+            ]
+        );
+        assert!(matches!(outputs[0], Output::Will { .. }));
+
+        // death certificate and birth carry the same bdSeq; only the birth has a seq
+        assert_eq!(metric_names(payload(&outputs[0])), vec!["bdSeq"]);
+        assert_eq!(seq(payload(&outputs[0])), None);
+        assert_eq!(metric_names(payload(&outputs[1])), vec!["bdSeq", "Node Control/Rebirth"]);
+        assert_eq!(metric_fields(payload(&outputs[0]))[0], metric_fields(payload(&outputs[1]))[0]);
+        assert_eq!(seq(payload(&outputs[1])), Some(0));
+
+        // every tagged point is declared, this response's values filled in
+        let birth = metric_fields(payload(&outputs[2]));
+        let names: Vec<_> = birth.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["pump_on", "level", "status/running"]);
+        assert_eq!(birth[0].1, vec![(4, Field::Number(11)), (7, Field::Number(1))]);
+        assert_eq!(
+            birth[1].1,
+            vec![(3, Field::Number(900)), (4, Field::Number(10)), (13, Field::Number(42.5f64.to_bits()))]
+        );
+        assert_eq!(birth[2].1, vec![(3, Field::Number(900)), (4, Field::Number(11)), (14, Field::Number(1))]);
+        assert_eq!(seq(payload(&outputs[2])), Some(1));
+        assert_eq!(fields(payload(&outputs[2]))[0], (1, Field::Number(1000)));
+    }
+
+    #[test]
+    fn test_later_reports_are_ddata_in_sequence() {
+        let (node, map) = (node(), map());
+        let mut session = Session::new();
+        session.data(&node, &map, 1, Table::HoldingRegister, &registers(1.0), &[], 0, 0);
+
+        let coils = TelemetryData::Bits(vec![Bit { address: 4, value: true, label: Some("pump_on".to_string()), description: None }]);
+        let outputs = session.data(&node, &map, 1, Table::Coil, &coils, &[], 0, 0);
+        assert_eq!(topics(&outputs), vec!["spBv1.0/plant/DDATA/gateway1/unit_1"]);
+        assert_eq!(metric_names(payload(&outputs[0])), vec!["pump_on"]);
+        assert_eq!(seq(payload(&outputs[0])), Some(2));
+
+        // 256 messages later the sequence has wrapped
+        for _ in 0..255 {
+            session.data(&node, &map, 1, Table::HoldingRegister, &registers(1.0), &[], 0, 0);
+        }
+        let outputs = session.data(&node, &map, 1, Table::HoldingRegister, &registers(1.0), &[], 0, 0);
+        assert_eq!(seq(payload(&outputs[0])), Some(2));
+
+        // untagged points alone publish nothing
+        let untagged = TelemetryData::Registers(Vec::new());
+        assert!(session.data(&node, &map, 1, Table::HoldingRegister, &untagged, &[], 0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_device_death_and_rebirth() {
+        let (node, map) = (node(), map());
+        let mut session = Session::new();
+        assert!(session.device_death(&node, 1, 0).is_none());
+        assert!(session.rebirth(&node, &map, 0).is_empty());
+
+        session.data(&node, &map, 1, Table::HoldingRegister, &registers(1.0), &[], 0, 0);
+        let death = session.device_death(&node, 1, 0).unwrap();
+        assert_eq!(topics(std::slice::from_ref(&death)), vec!["spBv1.0/plant/DDEATH/gateway1/unit_1"]);
+        assert_eq!(metric_names(payload(&death)), Vec::<String>::new());
+        assert_eq!(seq(payload(&death)), Some(2));
+
+        // back from the dead - born again, without a new node birth
+        let outputs = session.data(&node, &map, 1, Table::HoldingRegister, &registers(2.0), &[], 0, 0);
+        assert_eq!(topics(&outputs), vec!["spBv1.0/plant/DBIRTH/gateway1/unit_1"]);
+
+        // rebirth repeats both births with the last values, no new will
+        let outputs = session.rebirth(&node, &map, 0);
+        assert_eq!(
+            topics(&outputs),
+            vec!["spBv1.0/plant/NBIRTH/gateway1", "spBv1.0/plant/DBIRTH/gateway1/unit_1"]
+        );
+        assert_eq!(seq(payload(&outputs[0])), Some(0));
+        let level = &metric_fields(payload(&outputs[1]))[1];
+        assert_eq!(level.1[2], (13, Field::Number(2.0f64.to_bits())));
+    }
+}
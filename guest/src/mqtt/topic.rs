@@ -0,0 +1,479 @@
+// guest/src/mqtt/topic.rs
+// mqtt topic templates, e.g. "{site}/{area}/unit_{unit_id}/{function}".
+// templates are parsed and checked once at startup so that publishing can
+// only render valid topic names: no wildcards or nul, no broker-reserved
+// '$' prefix, and nothing longer than mqtt allows. placeholder values are
+// numbers, fixed names, configuration validated the same way (site, area
+// and tag names), or frame meta from the host - bounded in length,
+// percent-encoded when rendered and never empty - so a template that passes
+// here cannot go wrong later.
+
+use crate::frame_meta;
+
+/// longest topic name mqtt can carry, behind its two-byte length prefix
+pub const MAX_TOPIC_LEN: usize = 65535;
+
+/// longest function name, "read_device_identification"
+const MAX_FUNCTION_LEN: usize = 26;
+
+/// longest alarm level name, "hihi" or "lolo"
+const MAX_LEVEL_LEN: usize = 4;
+
+/// a value a template can substitute
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Site,          // configured site name
+    Area,          // configured area name
+    UnitId,        // modbus unit id, in decimal
+    Function,      // e.g. "read_holding_registers"
+    Tag,           // tag name from the register map
+    Level,         // alarm level, e.g. "hihi"
+    Source,        // frame source uri, percent-encoded
+    ConnectionId,  // frame connection id, percent-encoded
+}
+
+impl Field {
+    const ALL: [Self; 8] = [
+        Self::Site,
+        Self::Area,
+        Self::UnitId,
+        Self::Function,
+        Self::Tag,
+        Self::Level,
+        Self::Source,
+        Self::ConnectionId,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Site => "site",
+            Self::Area => "area",
+            Self::UnitId => "unit_id",
+            Self::Function => "function",
+            Self::Tag => "tag",
+            Self::Level => "level",
+            Self::Source => "source",
+            Self::ConnectionId => "connection_id",
+        }
+    }
+}
+
+/// what a topic carries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Telemetry,   // read responses
+    Events,      // status bit changes
+    Alarms,      // limit alarms raised and cleared
+    AlarmState,  // retained state of each alarm
+    Security,    // anomaly detections
+    Status,      // exception responses
+    Devices,     // retained device identities
+}
+
+impl Kind {
+    const ALL: [Self; 7] = [
+        Self::Telemetry,
+        Self::Events,
+        Self::Alarms,
+        Self::AlarmState,
+        Self::Security,
+        Self::Status,
+        Self::Devices,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Telemetry => "telemetry",
+            Self::Events => "events",
+            Self::Alarms => "alarms",
+            Self::AlarmState => "alarm state",
+            Self::Security => "security",
+            Self::Status => "status",
+            Self::Devices => "devices",
+        }
+    }
+
+    /// the topic used when none is configured
+    fn default_template(self) -> &'static str {
+        match self {
+            Self::Telemetry => "ics/telemetry/unit_{unit_id}",
+            Self::Events => "ics/events/unit_{unit_id}",
+            Self::Alarms => "ics/alarms/unit_{unit_id}",
+            Self::AlarmState => "ics/alarms/unit_{unit_id}/{tag}/{level}",
+            Self::Security => "ics/security/unit_{unit_id}",
+            Self::Status => "ics/status/unit_{unit_id}",
+            Self::Devices => "ics/devices/unit_{unit_id}",
+        }
+    }
+
+    /// placeholders the messages of this kind have values for.
+    /// telemetry with {tag} is published one point per message. alarm
+    /// states also change on operator commands, which have no frame meta.
+    fn fields(self) -> &'static [Field] {
+        use Field::*;
+        match self {
+            Self::Telemetry => &[Site, Area, UnitId, Function, Tag, Source, ConnectionId],
+            Self::Events | Self::Security => &[Site, Area, UnitId, Tag, Source, ConnectionId],
+            Self::Alarms => &[Site, Area, UnitId, Tag, Level, Source, ConnectionId],
+            Self::AlarmState => &[Site, Area, UnitId, Tag, Level],
+            Self::Status => &[Site, Area, UnitId, Function, Source, ConnectionId],
+            Self::Devices => &[Site, Area, UnitId, Source, ConnectionId],
+        }
+    }
+
+    /// placeholders a template must use - retained alarm states would
+    /// overwrite each other unless every alarm has a topic of its own
+    fn required(self) -> &'static [Field] {
+        match self {
+            Self::AlarmState => &[Field::UnitId, Field::Tag, Field::Level],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Field(Field),
+}
+
+/// a parsed topic template
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// parse `text` as a template for `kind`, rejecting unknown or
+    /// unavailable placeholders, unbalanced braces and wildcard characters
+    pub fn parse(kind: Kind, text: &str) -> Result<Self, String> {
+        if text.is_empty() {
+            return Err("topic is empty".to_string());
+        }
+        if text.starts_with('$') {
+            return Err("topics starting with '$' are reserved by the broker".to_string());
+        }
+
+        let mut segments = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(['{', '}']) {
+            if rest[start..].starts_with('}') {
+                return Err("unmatched '}'".to_string());
+            }
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_string()));
+            }
+            let after = &rest[start + 1..];
+            let end = after.find('}').ok_or("unclosed '{'")?;
+            let name = &after[..end];
+            let field = Field::ALL
+                .into_iter()
+                .find(|f| f.name() == name)
+                .ok_or_else(|| format!("unknown placeholder {{{}}}", name))?;
+            if !kind.fields().contains(&field) {
+                return Err(format!("{{{}}} is not available in {} topics", name, kind.name()));
+            }
+            segments.push(Segment::Field(field));
+            rest = &after[end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+
+        let template = Self { segments };
+        let text_chars = template.segments.iter().filter_map(|s| match s {
+            Segment::Text(text) => Some(text),
+            Segment::Field(_) => None,
+        });
+        if text_chars.flat_map(|t| t.chars()).any(|c| matches!(c, '+' | '#' | '\0')) {
+            return Err("topic cannot contain '+', '#' or nul".to_string());
+        }
+        if let Some(missing) = kind.required().iter().find(|&&f| !template.uses(f)) {
+            return Err(format!("{} topics must use {{{}}}", kind.name(), missing.name()));
+        }
+        Ok(template)
+    }
+
+    pub fn uses(&self, field: Field) -> bool {
+        self.segments.contains(&Segment::Field(field))
+    }
+
+    fn starts_with(&self, field: Field) -> bool {
+        self.segments.first() == Some(&Segment::Field(field))
+    }
+
+    /// longest topic this template can render, given the longest value of each field
+    fn max_len(&self, longest: impl Fn(Field) -> usize) -> usize {
+        self.segments
+            .iter()
+            .map(|s| match s {
+                Segment::Text(text) => text.len(),
+                Segment::Field(field) => longest(*field),
+            })
+            .sum()
+    }
+}
+
+/// placeholder values for one message - ones its kind doesn't use stay empty
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TopicValues<'a> {
+    pub unit_id: u8,
+    pub function: &'a str,
+    pub tag: &'a str,
+    pub level: &'a str,
+    pub source: &'a str,
+    pub connection_id: &'a str,
+}
+
+/// a template per kind of message, plus the site and area they share
+#[derive(Debug, Clone, PartialEq)]
+pub struct Topics {
+    site: String,
+    area: String,
+    templates: Vec<Template>,  // indexed by kind
+}
+
+impl Default for Topics {
+    /// the built-in ics/... topics
+    fn default() -> Self {
+        Self {
+            site: String::new(),
+            area: String::new(),
+            templates: Kind::ALL
+                .into_iter()
+                .map(|kind| Template::parse(kind, kind.default_template()).unwrap_or_default())
+                .collect(),
+        }
+    }
+}
+
+impl Topics {
+    /// build from configured templates, bounding {tag} by the longest tag
+    /// name. a template that fails checks falls back to its default, and
+    /// the reason is returned for metrics.
+    pub fn new(site: String, area: String, configured: Vec<(Kind, String)>, longest_tag: usize) -> (Self, Vec<String>) {
+        let mut topics = Self { site, area, ..Self::default() };
+        let mut errors = Vec::new();
+        for (kind, text) in configured {
+            match topics.check(kind, &text, longest_tag) {
+                Ok(template) => topics.templates[kind as usize] = template,
+                Err(msg) => errors.push(format!(
+                    "{} topic '{}' falls back to '{}': {}",
+                    kind.name(),
+                    text,
+                    kind.default_template(),
+                    msg
+                )),
+            }
+        }
+        (topics, errors)
+    }
+
+    fn check(&self, kind: Kind, text: &str, longest_tag: usize) -> Result<Template, String> {
+        let template = Template::parse(kind, text)?;
+        for (field, value) in [(Field::Site, &self.site), (Field::Area, &self.area)] {
+            if template.uses(field) && (value.is_empty() || value.contains(['/', '+', '#', '\0'])) {
+                return Err(format!("{} '{}' must be a single topic level", field.name(), value));
+            }
+            if template.starts_with(field) && value.starts_with('$') {
+                return Err(format!("{} '{}' would start a broker-reserved '$' topic", field.name(), value));
+            }
+        }
+        let len = template.max_len(|field| match field {
+            Field::Site => self.site.len(),
+            Field::Area => self.area.len(),
+            Field::UnitId => 3,
+            Field::Function => MAX_FUNCTION_LEN,
+            Field::Tag => longest_tag,
+            Field::Level => MAX_LEVEL_LEN,
+            // every byte could need encoding
+            Field::Source | Field::ConnectionId => frame_meta::MAX_LEN * 3,
+        });
+        if len > MAX_TOPIC_LEN {
+            return Err(format!("topic can reach {} bytes, over the mqtt limit of {}", len, MAX_TOPIC_LEN));
+        }
+        Ok(template)
+    }
+
+    /// whether topics of `kind` name the tag - telemetry is then
+    /// published one point per message
+    pub fn uses_tag(&self, kind: Kind) -> bool {
+        self.templates[kind as usize].uses(Field::Tag)
+    }
+
+    pub fn render(&self, kind: Kind, values: &TopicValues) -> String {
+        let mut topic = String::new();
+        for segment in &self.templates[kind as usize].segments {
+            match segment {
+                Segment::Text(text) => topic.push_str(text),
+                Segment::Field(Field::Site) => topic.push_str(&self.site),
+                Segment::Field(Field::Area) => topic.push_str(&self.area),
+                Segment::Field(Field::UnitId) => topic.push_str(&values.unit_id.to_string()),
+                Segment::Field(Field::Function) => topic.push_str(values.function),
+                Segment::Field(Field::Tag) => topic.push_str(values.tag),
+                Segment::Field(Field::Level) => topic.push_str(values.level),
+                Segment::Field(Field::Source) => encode(&mut topic, values.source),
+                Segment::Field(Field::ConnectionId) => encode(&mut topic, values.connection_id),
+            }
+        }
+        topic
+    }
+}
+
+/// append `value` with level separators, wildcards and nul percent-encoded,
+/// and '%' too so the original can be recovered. a leading '$' is encoded
+/// since the value may start the topic, and an empty value becomes "_" so
+/// it still fills its level.
+fn encode(topic: &mut String, value: &str) {
+    if value.is_empty() {
+        topic.push('_');
+        return;
+    }
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            '/' | '+' | '#' | '%' | '\0' => topic.push_str(&format!("%{:02X}", c as u8)),
+            '$' if i == 0 => topic.push_str("%24"),
+            c => topic.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::function::FunctionCode;
+
+    fn topics(configured: &[(Kind, &str)]) -> (Topics, Vec<String>) {
+        let configured = configured.iter().map(|(kind, text)| (*kind, text.to_string())).collect();
+        Topics::new("plant1".to_string(), "boilers".to_string(), configured, 16)
+    }
+
+    fn values() -> TopicValues<'static> {
+        TopicValues {
+            unit_id: 7,
+            function: "read_holding_registers",
+            tag: "boiler_temp",
+            level: "hi",
+            source: "modbus://10.0.0.50:502",
+            connection_id: "plc-north",
+        }
+    }
+
+    #[test]
+    fn test_defaults() {
+        let topics = Topics::default();
+        for kind in Kind::ALL {
+            assert_eq!(Template::parse(kind, kind.default_template()).map(|_| ()), Ok(()));
+        }
+        assert_eq!(topics.render(Kind::Telemetry, &values()), "ics/telemetry/unit_7");
+        assert_eq!(topics.render(Kind::AlarmState, &values()), "ics/alarms/unit_7/boiler_temp/hi");
+        assert!(!topics.uses_tag(Kind::Telemetry));
+
+        let longest = FunctionCode::ReadDeviceIdentification.name().len();
+        assert_eq!(longest, MAX_FUNCTION_LEN);
+    }
+
+    #[test]
+    fn test_render() {
+        let (topics, errors) = topics(&[
+            (Kind::Telemetry, "{site}/{area}/{unit_id}/{function}/{tag}"),
+            (Kind::Status, "{site}/status/unit-{unit_id}"),
+        ]);
+        assert!(errors.is_empty());
+        assert_eq!(
+            topics.render(Kind::Telemetry, &values()),
+            "plant1/boilers/7/read_holding_registers/boiler_temp"
+        );
+        assert_eq!(topics.render(Kind::Status, &values()), "plant1/status/unit-7");
+        assert!(topics.uses_tag(Kind::Telemetry));
+        // unconfigured kinds keep their default
+        assert_eq!(topics.render(Kind::Events, &values()), "ics/events/unit_7");
+    }
+
+    #[test]
+    fn test_frame_meta_is_encoded() {
+        let (topics, errors) = topics(&[(Kind::Telemetry, "{connection_id}/{source}/{unit_id}")]);
+        assert!(errors.is_empty());
+        assert_eq!(topics.render(Kind::Telemetry, &values()), "plc-north/modbus:%2F%2F10.0.0.50:502/7");
+
+        let values = TopicValues { source: "a+b#c%d\0", ..values() };
+        assert_eq!(topics.render(Kind::Telemetry, &values), "plc-north/a%2Bb%23c%25d%00/7");
+    }
+
+    #[test]
+    fn test_frame_meta_cannot_reserve_or_empty_a_topic() {
+        let (topics, errors) = topics(&[(Kind::Telemetry, "{source}/x$"), (Kind::Status, "{connection_id}")]);
+        assert!(errors.is_empty());
+
+        let values = TopicValues { source: "$SYS$/broker", connection_id: "", ..values() };
+        assert_eq!(topics.render(Kind::Telemetry, &values), "%24SYS$%2Fbroker/x$");
+        assert_eq!(topics.render(Kind::Status, &values), "_");
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_templates() {
+        let bad = [
+            (Kind::Telemetry, ""),
+            (Kind::Telemetry, "$SYS/{unit_id}"),
+            (Kind::Telemetry, "ics/+/{unit_id}"),
+            (Kind::Telemetry, "ics/#"),
+            (Kind::Telemetry, "ics/{unit_id"),
+            (Kind::Telemetry, "ics/unit_id}"),
+            (Kind::Telemetry, "ics/{unit}"),
+            (Kind::Telemetry, "ics/{{unit_id}}"),
+            // no level on telemetry, no tag on device identities
+            (Kind::Telemetry, "ics/{level}"),
+            (Kind::Devices, "ics/{tag}"),
+            // every alarm state needs a topic of its own
+            (Kind::AlarmState, "ics/alarms/{unit_id}/{tag}"),
+            // alarm states also change without a frame
+            (Kind::AlarmState, "{source}/{unit_id}/{tag}/{level}"),
+        ];
+        for (kind, text) in bad {
+            assert!(Template::parse(kind, text).is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_invalid_templates_fall_back() {
+        let (topics, errors) = topics(&[(Kind::Telemetry, "ics/+/{unit_id}"), (Kind::Alarms, "a/{site}/{tag}")]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("telemetry topic 'ics/+/{unit_id}' falls back to 'ics/telemetry/unit_{unit_id}'"));
+        assert_eq!(topics.render(Kind::Telemetry, &values()), "ics/telemetry/unit_7");
+        assert_eq!(topics.render(Kind::Alarms, &values()), "a/plant1/boiler_temp");
+    }
+
+    #[test]
+    fn test_site_and_area_must_be_topic_levels() {
+        let configured = vec![(Kind::Telemetry, "{site}/{unit_id}".to_string()), (Kind::Events, "{area}/{unit_id}".to_string())];
+        let (topics, errors) = Topics::new("a/b".to_string(), String::new(), configured.clone(), 0);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(topics, Topics { site: "a/b".to_string(), ..Topics::default() });
+
+        // unused, they don't matter
+        let configured = vec![(Kind::Telemetry, "x/{unit_id}".to_string())];
+        assert!(Topics::new("a+".to_string(), String::new(), configured, 0).1.is_empty());
+    }
+
+    #[test]
+    fn test_site_and_area_cannot_reserve_a_topic() {
+        let configured = vec![(Kind::Telemetry, "{site}/{area}/{unit_id}".to_string())];
+        let (topics, errors) = Topics::new("$SYS".to_string(), "hall".to_string(), configured.clone(), 0);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(topics.templates[Kind::Telemetry as usize], Topics::default().templates[Kind::Telemetry as usize]);
+        assert!(Topics::new("plant".to_string(), "$hall".to_string(), configured.clone(), 0).1.is_empty());
+
+        // further in, '$' is an ordinary character
+        let configured = vec![(Kind::Telemetry, "x/{site}/{unit_id}".to_string())];
+        assert!(Topics::new("$SYS".to_string(), String::new(), configured, 0).1.is_empty());
+    }
+
+    #[test]
+    fn test_length_limit() {
+        let long = "a".repeat(MAX_TOPIC_LEN - 20);
+        let configured = vec![(Kind::Telemetry, format!("{}/{{tag}}", long))];
+        assert!(Topics::new(String::new(), String::new(), configured.clone(), 10).1.is_empty());
+        // a longer tag name could push the topic over the limit
+        assert_eq!(Topics::new(String::new(), String::new(), configured, 20).1.len(), 1);
+    }
+}
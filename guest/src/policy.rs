@@ -0,0 +1,189 @@
+// guest/src/policy.rs
+// site-specific function code policy, evaluated before any pdu is parsed.
+// FunctionCode::from_byte defines what the parser can understand; the policy
+// decides what a given site permits (e.g. coils allowed everywhere, input
+// registers forbidden on unit 7). rules are evaluated in order, first match
+// wins, like a firewall - the default action applies when nothing matches.
+// a response that answers no tracked request has no known addresses, so
+// address-scoped deny rules fail closed on it: otherwise the reply to a
+// request the policy denied, or a spoofed one, would pass as uncorrelated.
+
+use std::fmt;
+
+/// what to do with a frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Allow,
+    Deny,
+}
+
+/// one allow/deny rule. unset fields match anything.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    pub unit_id: Option<u8>,
+    pub function_code: Option<u8>,
+    pub address_range: Option<(u16, u16)>,  // inclusive start, end
+    pub action: Action,
+}
+
+/// ordered rule list plus the action when no rule matches
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    pub rules: Vec<Rule>,
+    pub default_action: Action,
+}
+
+impl Default for Policy {
+    /// no rules, allow everything the parser supports
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            default_action: Action::Allow,
+        }
+    }
+}
+
+/// the frame attributes a policy is evaluated against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Subject {
+    pub unit_id: u8,
+    pub function_code: u8,           // exception bit already masked off
+    pub range: Option<(u16, u16)>,   // inclusive, known only via the request
+}
+
+/// why a frame was denied
+#[derive(Debug, Clone, PartialEq)]
+pub struct Denial {
+    pub rule: Option<String>,  // None when the default action denied it
+    pub subject: Subject,
+}
+
+impl fmt::Display for Denial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.rule {
+            Some(rule) => write!(f, "rule '{}'", rule)?,
+            None => write!(f, "default policy")?,
+        }
+        write!(
+            f,
+            " denied function 0x{:02X} for unit {}",
+            self.subject.function_code, self.subject.unit_id
+        )?;
+        if let Some((start, end)) = self.subject.range {
+            write!(f, " at {}-{}", start, end)?;
+        }
+        Ok(())
+    }
+}
+
+impl Rule {
+    /// an address-scoped rule matches when the subject's range overlaps it.
+    /// with the range unknown, only a deny rule matches.
+    fn matches(&self, subject: &Subject) -> bool {
+        if self.unit_id.is_some_and(|u| u != subject.unit_id) {
+            return false;
+        }
+        if self.function_code.is_some_and(|f| f != subject.function_code) {
+            return false;
+        }
+        match (self.address_range, subject.range) {
+            (None, _) => true,
+            (Some(_), None) => self.action == Action::Deny,
+            (Some((start, end)), Some((lo, hi))) => lo <= end && start <= hi,
+        }
+    }
+}
+
+impl Policy {
+    /// first matching rule decides; otherwise the default action
+    pub fn evaluate(&self, subject: Subject) -> Result<(), Denial> {
+        let matched = self.rules.iter().find(|r| r.matches(&subject));
+        let action = matched.map_or(self.default_action, |r| r.action);
+        match action {
+            Action::Allow => Ok(()),
+            Action::Deny => Err(Denial {
+                rule: matched.map(|r| r.name.clone()),
+                subject,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, unit_id: Option<u8>, function_code: Option<u8>, address_range: Option<(u16, u16)>, action: Action) -> Rule {
+        Rule { name: name.to_string(), unit_id, function_code, address_range, action }
+    }
+
+    fn subject(unit_id: u8, function_code: u8, range: Option<(u16, u16)>) -> Subject {
+        Subject { unit_id, function_code, range }
+    }
+
+    #[test]
+    fn test_default_policy_allows() {
+        assert_eq!(Policy::default().evaluate(subject(1, 0x03, None)), Ok(()));
+    }
+
+    #[test]
+    fn test_first_match_wins() {
+        let policy = Policy {
+            rules: vec![
+                rule("no-input-regs-unit-7", Some(7), Some(0x04), None, Action::Deny),
+                rule("registers", None, Some(0x04), None, Action::Allow),
+                rule("holding", None, Some(0x03), None, Action::Allow),
+            ],
+            default_action: Action::Deny,
+        };
+
+        assert_eq!(policy.evaluate(subject(1, 0x04, None)), Ok(()));
+        assert_eq!(policy.evaluate(subject(1, 0x03, None)), Ok(()));
+
+        let denial = policy.evaluate(subject(7, 0x04, None)).unwrap_err();
+        assert_eq!(denial.rule.as_deref(), Some("no-input-regs-unit-7"));
+        assert_eq!(denial.to_string(), "rule 'no-input-regs-unit-7' denied function 0x04 for unit 7");
+
+        // coils match nothing and fall through to the default
+        let denial = policy.evaluate(subject(1, 0x01, None)).unwrap_err();
+        assert_eq!(denial.rule, None);
+    }
+
+    #[test]
+    fn test_address_range_overlap() {
+        let policy = Policy {
+            rules: vec![rule("protect-setpoints", None, None, Some((1000, 1099)), Action::Deny)],
+            default_action: Action::Allow,
+        };
+
+        assert!(policy.evaluate(subject(1, 0x03, Some((990, 1000)))).is_err());
+        assert!(policy.evaluate(subject(1, 0x03, Some((1099, 1200)))).is_err());
+        assert_eq!(policy.evaluate(subject(1, 0x03, Some((0, 999)))), Ok(()));
+        assert_eq!(policy.evaluate(subject(1, 0x03, Some((1100, 1200)))), Ok(()));
+        // range unknown - the deny rule fails closed
+        let denial = policy.evaluate(subject(1, 0x03, None)).unwrap_err();
+        assert_eq!(denial.rule.as_deref(), Some("protect-setpoints"));
+
+        let denial = policy.evaluate(subject(2, 0x03, Some((1000, 1009)))).unwrap_err();
+        assert_eq!(denial.to_string(), "rule 'protect-setpoints' denied function 0x03 for unit 2 at 1000-1009");
+    }
+
+    #[test]
+    fn test_unknown_range_skips_address_scoped_allow() {
+        let policy = Policy {
+            rules: vec![
+                rule("unit-7-setpoints", Some(7), Some(0x03), Some((1000, 1099)), Action::Deny),
+                rule("window", None, Some(0x03), Some((0, 99)), Action::Allow),
+            ],
+            default_action: Action::Deny,
+        };
+
+        // an allow rule never admits addresses it cannot see
+        assert_eq!(policy.evaluate(subject(1, 0x03, Some((0, 9)))), Ok(()));
+        assert_eq!(policy.evaluate(subject(1, 0x03, None)).unwrap_err().rule, None);
+        // deny rules still only apply to their own unit and function
+        assert!(policy.evaluate(subject(7, 0x03, None)).is_err());
+        assert_eq!(policy.evaluate(subject(7, 0x04, None)).unwrap_err().rule, None);
+    }
+}
@@ -0,0 +1,172 @@
+// guest/src/poll.rs
+// scan list for guest-driven polling through the modbus-client import.
+// the host calls poll on a timer with its monotonic clock; the scheduler
+// decides which reads are due, so the scan list lives inside the sandbox
+// and the host only moves bytes between the guest and the wire.
+
+use crate::modbus::function::{FunctionCode, ReadRequest};
+
+/// reply timeout used when the host supplies none
+pub const DEFAULT_TIMEOUT_MS: u32 = 1000;
+
+/// one read the gateway issues on a fixed interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollEntry {
+    pub name: String,
+    pub unit_id: u8,
+    pub request: ReadRequest,
+    pub interval_ms: u64,
+}
+
+impl PollEntry {
+    /// build an entry from raw config values, rejecting anything the
+    /// gateway would refuse to send (non-read functions, bad quantities)
+    pub fn new(
+        name: String,
+        unit_id: u8,
+        function_code: u8,
+        start_address: u16,
+        quantity: u16,
+        interval_ms: u32,
+    ) -> Result<Self, String> {
+        let function = FunctionCode::from_byte(function_code)
+            .filter(|f| f.is_bit_access() || f.is_register_access())
+            .ok_or_else(|| format!("function 0x{:02X} is not a data read", function_code))?;
+        if interval_ms == 0 {
+            return Err("poll interval must be at least 1 ms".to_string());
+        }
+        let request = ReadRequest {
+            function,
+            start_address,
+            quantity,
+        };
+        request.validate().map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            name,
+            unit_id,
+            request,
+            interval_ms: interval_ms as u64,
+        })
+    }
+}
+
+/// validated scan list plus the reply timeout for every read
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollSchedule {
+    pub entries: Vec<PollEntry>,
+    pub timeout_ms: u32,
+}
+
+impl Default for PollSchedule {
+    /// empty scan list - the gateway only handles frames the host pushes
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        }
+    }
+}
+
+/// tracks when each scan-list entry is next due
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    next_due: Vec<u64>,           // per entry, in host milliseconds
+    next_transaction_id: u16,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// entries whose interval has elapsed at `now_ms`, in scan-list order.
+    /// every entry is due on the first call. a late poll does not trigger
+    /// catch-up reads - the next read is one interval after this one.
+    pub fn due<'a>(&mut self, schedule: &'a PollSchedule, now_ms: u64) -> Vec<&'a PollEntry> {
+        self.next_due.resize(schedule.entries.len(), 0);
+
+        let mut due = Vec::new();
+        for (entry, next) in schedule.entries.iter().zip(self.next_due.iter_mut()) {
+            // host clock went backwards (e.g. restarted) - don't stall for ages
+            if *next > now_ms.saturating_add(entry.interval_ms) {
+                *next = now_ms;
+            }
+            if now_ms >= *next {
+                *next = now_ms.saturating_add(entry.interval_ms);
+                due.push(entry);
+            }
+        }
+        due
+    }
+
+    /// next modbus tcp transaction id, wrapping at u16::MAX
+    pub fn next_transaction_id(&mut self) -> u16 {
+        self.next_transaction_id = self.next_transaction_id.wrapping_add(1);
+        self.next_transaction_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, interval_ms: u32) -> PollEntry {
+        PollEntry::new(name.to_string(), 1, 0x03, 0, 10, interval_ms).unwrap()
+    }
+
+    fn names(due: Vec<&PollEntry>) -> Vec<&str> {
+        due.into_iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_entry_validation() {
+        assert!(PollEntry::new("coils".into(), 1, 0x01, 0, 2000, 100).is_ok());
+        assert!(PollEntry::new("write".into(), 1, 0x06, 0, 1, 100).is_err());
+        assert!(PollEntry::new("device id".into(), 1, 0x2B, 0, 1, 100).is_err());
+        assert!(PollEntry::new("too many".into(), 1, 0x03, 0, 126, 100).is_err());
+        assert!(PollEntry::new("wraps".into(), 1, 0x03, 0xFFFF, 2, 100).is_err());
+        assert!(PollEntry::new("flood".into(), 1, 0x03, 0, 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_intervals() {
+        let schedule = PollSchedule {
+            entries: vec![entry("fast", 100), entry("slow", 250)],
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        };
+        let mut scheduler = Scheduler::new();
+
+        assert_eq!(names(scheduler.due(&schedule, 1_000)), vec!["fast", "slow"]);
+        assert!(scheduler.due(&schedule, 1_050).is_empty());
+        assert_eq!(names(scheduler.due(&schedule, 1_100)), vec!["fast"]);
+        // late poll: one read each, no burst of missed intervals
+        assert_eq!(names(scheduler.due(&schedule, 2_000)), vec!["fast", "slow"]);
+        assert!(scheduler.due(&schedule, 2_099).is_empty());
+    }
+
+    #[test]
+    fn test_clock_reset() {
+        let schedule = PollSchedule {
+            entries: vec![entry("fast", 100)],
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        };
+        let mut scheduler = Scheduler::new();
+
+        assert_eq!(scheduler.due(&schedule, 5_000_000).len(), 1);
+        // host restarted its monotonic clock
+        assert_eq!(scheduler.due(&schedule, 10).len(), 1);
+        assert!(scheduler.due(&schedule, 20).is_empty());
+        assert_eq!(scheduler.due(&schedule, 110).len(), 1);
+    }
+
+    #[test]
+    fn test_transaction_ids_wrap() {
+        let mut scheduler = Scheduler::new();
+        scheduler.next_transaction_id = u16::MAX - 1;
+
+        assert_eq!(scheduler.next_transaction_id(), u16::MAX);
+        assert_eq!(scheduler.next_transaction_id(), 0);
+        assert_eq!(scheduler.next_transaction_id(), 1);
+    }
+}
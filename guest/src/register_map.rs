@@ -0,0 +1,637 @@
+// guest/src/register_map.rs
+// site register map: names registers and bits so historians receive
+// "boiler_temp" instead of holding register 0. points are keyed on unit id,
+// modbus data table and address - coil 5 and holding register 5 are different
+// points. tags only apply to correlated responses, since an uncorrelated
+// response has offsets rather than real addresses. a register tag may give a
+// data type spanning several registers, published as one decoded value,
+// a linear scaling that turns raw counts into engineering units, or named
+// bits of a 16-bit status word.
+
+use std::collections::HashMap;
+
+use crate::alarms::Limits;
+use crate::anomaly::AnomalyChecks;
+use crate::modbus::datatype::{self, ByteOrder, DataType, Value, MAX_STRING_REGISTERS};
+use crate::modbus::function::FunctionCode;
+use crate::mqtt::payload::{Bit, Register, StatusBit};
+use crate::report::Deadband;
+use crate::status_bits::StatusBits;
+
+/// the four modbus data tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Table {
+    Coil,
+    DiscreteInput,
+    InputRegister,
+    HoldingRegister,
+}
+
+impl Table {
+    /// table a read function addresses - None for non-data functions
+    pub fn for_function(function: FunctionCode) -> Option<Self> {
+        match function {
+            FunctionCode::ReadCoils => Some(Self::Coil),
+            FunctionCode::ReadDiscreteInputs => Some(Self::DiscreteInput),
+            FunctionCode::ReadHoldingRegisters => Some(Self::HoldingRegister),
+            FunctionCode::ReadInputRegisters => Some(Self::InputRegister),
+            FunctionCode::ReadDeviceIdentification => None,
+        }
+    }
+}
+
+/// identifies one point on one slave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointKey {
+    pub unit_id: u8,
+    pub table: Table,
+    pub address: u16,
+}
+
+/// downstream name and metadata for a point
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    pub description: Option<String>,
+    pub unit: Option<String>,  // unit of measure, e.g. "degC"
+    pub data_type: DataType,   // registers only - bits are always booleans
+    pub byte_order: ByteOrder,
+    pub scaling: Option<Scaling>,
+    pub status_bits: Option<StatusBits>,  // uint16 registers only
+    pub deadband: Option<Deadband>,       // publish by exception
+    pub limits: Option<Limits>,           // numeric registers only
+    pub anomaly: Option<AnomalyChecks>,   // numeric registers only
+}
+
+#[cfg(test)]
+impl Tag {
+    /// a bare uint16 tag - tests set the rest with struct update syntax
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: None,
+            unit: None,
+            data_type: DataType::Uint16,
+            byte_order: ByteOrder::Abcd,
+            scaling: None,
+            status_bits: None,
+            deadband: None,
+            limits: None,
+            anomaly: None,
+        }
+    }
+}
+
+/// engineering-unit conversion: raw * gain + offset, then clamped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scaling {
+    pub gain: f64,
+    pub offset: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl Scaling {
+    /// scale a numeric value - text passes through untouched
+    pub fn apply(&self, value: Value) -> Value {
+        let Some(raw) = value.as_f64() else {
+            return value;
+        };
+        let mut scaled = raw * self.gain + self.offset;
+        // f64::clamp panics on bad bounds; max/min never do
+        if let Some(min) = self.min {
+            scaled = scaled.max(min);
+        }
+        if let Some(max) = self.max {
+            scaled = scaled.min(max);
+        }
+        Value::Float(scaled)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.gain.is_finite() || !self.offset.is_finite() {
+            return Err("scaling gain and offset must be finite".to_string());
+        }
+        let bounds = [self.min, self.max];
+        if bounds.iter().flatten().any(|b| b.is_nan()) {
+            return Err("scaling limits must be numbers".to_string());
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                return Err(format!("scaling min {} is above max {}", min, max));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// what to publish for points without a tag
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unmapped {
+    PassThrough,  // publish with address only
+    Drop,         // publish mapped points only
+}
+
+/// tags per point plus the unmapped-point setting
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterMap {
+    tags: HashMap<PointKey, Tag>,
+    pub unmapped: Unmapped,
+    pub include_raw: bool,  // add the received registers to every tagged value
+    pub max_silence_ms: Option<u64>,  // republish deadbanded points at least this often
+}
+
+impl Default for RegisterMap {
+    /// no tags, every point passes through unlabelled
+    fn default() -> Self {
+        Self::new(Unmapped::PassThrough)
+    }
+}
+
+impl RegisterMap {
+    pub fn new(unmapped: Unmapped) -> Self {
+        Self {
+            tags: HashMap::new(),
+            unmapped,
+            include_raw: false,
+            max_silence_ms: None,
+        }
+    }
+
+    /// add a tag, rejecting empty or unusable names, bad data types and tags whose
+    /// registers overlap a point that is already mapped
+    pub fn insert(&mut self, key: PointKey, tag: Tag) -> Result<(), String> {
+        if tag.name.is_empty() {
+            return Err(format!("empty tag name for unit {} address {}", key.unit_id, key.address));
+        }
+        // tag names are topic levels, e.g. in alarm state topics, and
+        // "{tag}/..." would put a '$' name in the broker-reserved namespace
+        if tag.name.contains(['/', '+', '#', '\0']) {
+            return Err(format!("tag '{}': names cannot contain '/', '+', '#' or nul", tag.name));
+        }
+        if tag.name.starts_with('$') {
+            return Err(format!("tag '{}': names cannot start with '$'", tag.name));
+        }
+        // operator commands name the tag, so it must be unique per slave
+        if self.find(key.unit_id, &tag.name).is_some() {
+            return Err(format!("tag '{}' is already used on unit {}", tag.name, key.unit_id));
+        }
+        let count = span(&key, &tag);
+        if let DataType::Ascii(registers) = tag.data_type {
+            if registers == 0 || registers > MAX_STRING_REGISTERS {
+                return Err(format!("tag '{}': ascii length must be 1-{} registers", tag.name, MAX_STRING_REGISTERS));
+            }
+        }
+        if let Some(scaling) = &tag.scaling {
+            if matches!(tag.data_type, DataType::Ascii(_)) {
+                return Err(format!("tag '{}': ascii values cannot be scaled", tag.name));
+            }
+            scaling.validate().map_err(|msg| format!("tag '{}': {}", tag.name, msg))?;
+        }
+        if let Some(status) = &tag.status_bits {
+            let word = tag.data_type == DataType::Uint16 && tag.scaling.is_none();
+            if !word || matches!(key.table, Table::Coil | Table::DiscreteInput) {
+                return Err(format!("tag '{}': status bits need an unscaled uint16 register", tag.name));
+            }
+            status.validate().map_err(|msg| format!("tag '{}': {}", tag.name, msg))?;
+        }
+        // limits and anomaly checks need a published number to look at
+        let replaced = tag.status_bits.as_ref().is_some_and(|s| s.replace_word);
+        let numeric = !matches!(key.table, Table::Coil | Table::DiscreteInput)
+            && !matches!(tag.data_type, DataType::Ascii(_))
+            && !replaced;
+        if let Some(limits) = &tag.limits {
+            if !numeric {
+                return Err(format!("tag '{}': alarm limits need a numeric register", tag.name));
+            }
+            limits.validate().map_err(|msg| format!("tag '{}': {}", tag.name, msg))?;
+        }
+        if let Some(checks) = &tag.anomaly {
+            if !numeric {
+                return Err(format!("tag '{}': anomaly checks need a numeric register", tag.name));
+            }
+            checks.validate().map_err(|msg| format!("tag '{}': {}", tag.name, msg))?;
+        }
+        if let Some(deadband) = &tag.deadband {
+            deadband.validate().map_err(|msg| format!("tag '{}': {}", tag.name, msg))?;
+        }
+        if key.address as usize + count > u16::MAX as usize + 1 {
+            return Err(format!("tag '{}' runs past address 65535", tag.name));
+        }
+        if let Some(existing) = self.overlapping(&key, count) {
+            return Err(format!(
+                "tag '{}' at unit {} address {} overlaps '{}'",
+                tag.name, key.unit_id, key.address, existing.name
+            ));
+        }
+        self.tags.insert(key, tag);
+        Ok(())
+    }
+
+    /// a mapped point sharing any of the `count` addresses from `key`
+    fn overlapping(&self, key: &PointKey, count: usize) -> Option<&Tag> {
+        let at = |address: u16| PointKey { address, ..*key };
+        // a later tag starting inside the new span
+        let inside = (0..count).find_map(|i| self.get(&at(key.address.wrapping_add(i as u16))));
+        // an earlier tag whose span reaches the new start
+        let before = (1..MAX_STRING_REGISTERS as u16)
+            .take_while(|&back| back <= key.address)
+            .find_map(|back| {
+                let earlier = at(key.address - back);
+                self.get(&earlier).filter(|t| span(&earlier, t) > back as usize)
+            });
+        inside.or(before)
+    }
+
+    pub fn get(&self, key: &PointKey) -> Option<&Tag> {
+        self.tags.get(key)
+    }
+
+    /// length of the longest tag name, for bounding topic lengths
+    pub fn longest_name(&self) -> usize {
+        self.tags.values().map(|t| t.name.len()).max().unwrap_or(0)
+    }
+
+    /// every tag on `unit_id`, ordered by table then address
+    pub fn unit_tags(&self, unit_id: u8) -> Vec<(PointKey, &Tag)> {
+        let mut tags: Vec<_> = self
+            .tags
+            .iter()
+            .filter(|(key, _)| key.unit_id == unit_id)
+            .map(|(key, tag)| (*key, tag))
+            .collect();
+        tags.sort_by_key(|(key, _)| (key.table as u8, key.address));
+        tags
+    }
+
+    /// the point tagged `name` on `unit_id`
+    pub fn find(&self, unit_id: u8, name: &str) -> Option<(PointKey, &Tag)> {
+        self.tags
+            .iter()
+            .find(|(key, tag)| key.unit_id == unit_id && tag.name == name)
+            .map(|(key, tag)| (*key, tag))
+    }
+
+    /// label and decode register values `function` read from `start`. without
+    /// a start address (uncorrelated response) nothing is mapped, so drop
+    /// publishes nothing. a typed tag the response only partly covers, or whose
+    /// registers don't decode, is treated as unmapped rather than guessed at.
+    pub fn registers(&self, unit_id: u8, function: FunctionCode, start: Option<u16>, values: &[u16]) -> Vec<Register> {
+        let table = Table::for_function(function);
+        let mut registers = Vec::new();
+        let mut i = 0;
+        while i < values.len() {
+            let address = start.unwrap_or(0).wrapping_add(i as u16);
+            let tag = start
+                .zip(table)
+                .and_then(|(_, table)| self.get(&PointKey { unit_id, table, address }));
+
+            let decoded = tag.and_then(|tag| {
+                let words = values.get(i..i + tag.data_type.register_count())?;
+                let value = datatype::decode(words, tag.data_type, tag.byte_order).ok()?;
+                Some((tag, words, value))
+            });
+            if let Some((tag, words, value)) = decoded {
+                // the word's named bits are published instead, by status_bits
+                let replaced = tag.status_bits.as_ref().is_some_and(|s| s.replace_word);
+                if !replaced {
+                    registers.push(Register {
+                        address,
+                        value: match tag.scaling {
+                            Some(scaling) => scaling.apply(value),
+                            None => value,
+                        },
+                        data_type: (tag.data_type != DataType::Uint16).then(|| tag.data_type.name()),
+                        label: Some(tag.name.clone()),
+                        description: tag.description.clone(),
+                        unit: tag.unit.clone(),
+                        raw: self.include_raw.then(|| words.to_vec()),
+                    });
+                }
+                i += tag.data_type.register_count();
+                continue;
+            }
+
+            if self.unmapped == Unmapped::PassThrough {
+                registers.push(Register {
+                    address,
+                    value: Value::Unsigned(values[i] as u64),
+                    data_type: None,
+                    label: None,
+                    description: None,
+                    unit: None,
+                    raw: None,
+                });
+            }
+            i += 1;
+        }
+        registers
+    }
+
+    /// named bits of the status words `function` read from `start`.
+    /// uncorrelated responses have no real addresses, so they have none.
+    pub fn status_bits(&self, unit_id: u8, function: FunctionCode, start: Option<u16>, values: &[u16]) -> Vec<StatusBit> {
+        let (Some(start), Some(table)) = (start, Table::for_function(function)) else {
+            return Vec::new();
+        };
+        values
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &word)| {
+                let address = start.wrapping_add(i as u16);
+                self.get(&PointKey { unit_id, table, address })
+                    .and_then(|tag| tag.status_bits.as_ref())
+                    .into_iter()
+                    .flat_map(move |status| status.extract(address, word))
+            })
+            .collect()
+    }
+
+    /// label coil / discrete input states `function` read from `start`
+    pub fn bits(&self, unit_id: u8, function: FunctionCode, start: Option<u16>, values: &[bool]) -> Vec<Bit> {
+        let table = Table::for_function(function);
+        values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &value)| {
+                let address = start.unwrap_or(0).wrapping_add(i as u16);
+                let tag = start
+                    .zip(table)
+                    .and_then(|(_, table)| self.get(&PointKey { unit_id, table, address }));
+                if tag.is_none() && self.unmapped == Unmapped::Drop {
+                    return None;
+                }
+                Some(Bit {
+                    address,
+                    value,
+                    label: tag.map(|t| t.name.clone()),
+                    description: tag.and_then(|t| t.description.clone()),
+                })
+            })
+            .collect()
+    }
+}
+
+/// registers a tag covers - bits always cover one address
+fn span(key: &PointKey, tag: &Tag) -> usize {
+    match key.table {
+        Table::Coil | Table::DiscreteInput => 1,
+        Table::InputRegister | Table::HoldingRegister => tag.data_type.register_count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status_bits::BitField;
+
+    fn key(address: u16) -> PointKey {
+        PointKey {
+            unit_id: 1,
+            table: Table::HoldingRegister,
+            address,
+        }
+    }
+
+    fn tag(name: &str) -> Tag {
+        Tag {
+            description: Some("boiler outlet temperature".to_string()),
+            unit: Some("degC".to_string()),
+            ..Tag::named(name)
+        }
+    }
+
+    fn status_word(name: &str, replace_word: bool) -> Tag {
+        Tag {
+            status_bits: Some(StatusBits {
+                fields: vec![
+                    BitField { bit: 0, name: "running".to_string() },
+                    BitField { bit: 3, name: "pump_running".to_string() },
+                ],
+                replace_word,
+            }),
+            ..tag(name)
+        }
+    }
+
+    fn scaled(name: &str, gain: f64, offset: f64, min: Option<f64>, max: Option<f64>) -> Tag {
+        Tag {
+            scaling: Some(Scaling { gain, offset, min, max }),
+            ..tag(name)
+        }
+    }
+
+    fn typed(name: &str, data_type: DataType, byte_order: ByteOrder) -> Tag {
+        Tag {
+            data_type,
+            byte_order,
+            ..tag(name)
+        }
+    }
+
+    fn map(unmapped: Unmapped) -> RegisterMap {
+        let mut map = RegisterMap::new(unmapped);
+        map.insert(key(101), tag("boiler_temp")).unwrap();
+        map
+    }
+
+    #[test]
+    fn test_pass_through_labels_mapped_registers() {
+        let registers = map(Unmapped::PassThrough).registers(1, FunctionCode::ReadHoldingRegisters, Some(100), &[7, 8, 9]);
+
+        assert_eq!(registers.len(), 3);
+        assert_eq!(registers[0].label, None);
+        assert_eq!(registers[1].address, 101);
+        assert_eq!(registers[1].value, Value::Unsigned(8));
+        assert_eq!(registers[1].data_type, None);
+        assert_eq!(registers[1].label.as_deref(), Some("boiler_temp"));
+        assert_eq!(registers[1].unit.as_deref(), Some("degC"));
+    }
+
+    #[test]
+    fn test_drop_keeps_only_mapped_registers() {
+        let registers = map(Unmapped::Drop).registers(1, FunctionCode::ReadHoldingRegisters, Some(100), &[7, 8, 9]);
+
+        assert_eq!(registers.len(), 1);
+        assert_eq!(registers[0].address, 101);
+        assert_eq!(registers[0].value, Value::Unsigned(8));
+    }
+
+    #[test]
+    fn test_key_includes_unit_and_table() {
+        let map = map(Unmapped::Drop);
+
+        assert!(map.registers(2, FunctionCode::ReadHoldingRegisters, Some(101), &[1]).is_empty());
+        assert!(map.registers(1, FunctionCode::ReadInputRegisters, Some(101), &[1]).is_empty());
+        assert!(map.bits(1, FunctionCode::ReadCoils, Some(101), &[true]).is_empty());
+    }
+
+    #[test]
+    fn test_uncorrelated_response_is_never_labelled() {
+        // offset 101 is not address 101 when the request is unknown
+        let registers = map(Unmapped::PassThrough).registers(1, FunctionCode::ReadHoldingRegisters, None, &[0; 102]);
+        assert!(registers.iter().all(|r| r.label.is_none()));
+
+        assert!(map(Unmapped::Drop).registers(1, FunctionCode::ReadHoldingRegisters, None, &[0; 102]).is_empty());
+    }
+
+    #[test]
+    fn test_insert_rejects_duplicates_and_empty_names() {
+        let mut map = map(Unmapped::PassThrough);
+
+        assert!(map.insert(key(101), tag("other")).is_err());
+        assert!(map.insert(key(102), tag("")).is_err());
+        assert!(map.insert(key(102), tag("boiler/temp")).is_err());
+        assert!(map.insert(key(102), tag("boiler#")).is_err());
+        assert!(map.insert(key(102), tag("$boiler")).is_err());
+        assert!(map.insert(key(110), tag("boiler$")).is_ok());
+        // names are unique per slave, since commands address tags by name
+        assert!(map.insert(key(103), tag("boiler_temp")).is_err());
+        assert!(map.insert(PointKey { unit_id: 2, ..key(103) }, tag("boiler_temp")).is_ok());
+        assert_eq!(map.get(&key(101)).map(|t| t.name.as_str()), Some("boiler_temp"));
+        assert_eq!(map.get(&key(102)), None);
+        assert_eq!(map.find(1, "boiler_temp").map(|(k, _)| k), Some(key(101)));
+    }
+
+    #[test]
+    fn test_typed_tag_spans_its_registers() {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+        map.insert(key(10), typed("flow", DataType::Float32, ByteOrder::Cdab)).unwrap();
+
+        // 123.456f32 = 0x42F6E979, low register first
+        let registers = map.registers(1, FunctionCode::ReadHoldingRegisters, Some(9), &[5, 0xE979, 0x42F6, 6]);
+
+        assert_eq!(registers.len(), 3);
+        assert_eq!(registers[0].value, Value::Unsigned(5));
+        assert_eq!(registers[1].address, 10);
+        assert_eq!(registers[1].value, Value::Float(123.456f32 as f64));
+        assert_eq!(registers[1].data_type, Some("float32"));
+        assert_eq!(registers[2].address, 12);
+    }
+
+    #[test]
+    fn test_partial_or_undecodable_typed_tag_is_unmapped() {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+        map.insert(key(10), typed("total", DataType::Uint32, ByteOrder::Abcd)).unwrap();
+        map.insert(key(20), typed("batch", DataType::Bcd16, ByteOrder::Abcd)).unwrap();
+
+        // the read stops halfway through the uint32
+        let registers = map.registers(1, FunctionCode::ReadHoldingRegisters, Some(10), &[1]);
+        assert_eq!(registers.len(), 1);
+        assert_eq!(registers[0].label, None);
+
+        // 0xA is not a decimal digit
+        let registers = map.registers(1, FunctionCode::ReadHoldingRegisters, Some(20), &[0x00A1]);
+        assert_eq!(registers[0].label, None);
+        assert_eq!(registers[0].value, Value::Unsigned(0xA1));
+
+        let mut drop = RegisterMap::new(Unmapped::Drop);
+        drop.insert(key(10), typed("total", DataType::Uint32, ByteOrder::Abcd)).unwrap();
+        assert!(drop.registers(1, FunctionCode::ReadHoldingRegisters, Some(10), &[1]).is_empty());
+    }
+
+    #[test]
+    fn test_insert_rejects_overlapping_spans() {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+        map.insert(key(10), typed("energy", DataType::Float64, ByteOrder::Abcd)).unwrap();
+
+        // 10-13 are taken
+        assert!(map.insert(key(13), tag("inside")).is_err());
+        assert!(map.insert(key(9), typed("reaches_in", DataType::Uint32, ByteOrder::Abcd)).is_err());
+        assert!(map.insert(key(9), tag("before")).is_ok());
+        assert!(map.insert(key(14), tag("after")).is_ok());
+
+        assert!(map.insert(key(65535), typed("wraps", DataType::Uint32, ByteOrder::Abcd)).is_err());
+        assert!(map.insert(key(100), typed("empty", DataType::Ascii(0), ByteOrder::Abcd)).is_err());
+        assert!(map.insert(key(100), typed("name", DataType::Ascii(8), ByteOrder::Badc)).is_ok());
+        assert!(map.insert(key(107), tag("in_name")).is_err());
+
+        // a coil at the same address is a different point
+        let coil = PointKey { table: Table::Coil, ..key(11) };
+        assert!(map.insert(coil, tag("valve")).is_ok());
+    }
+
+    #[test]
+    fn test_scaling_and_clamp() {
+        let scaling = Scaling { gain: 0.1, offset: -40.0, min: Some(-20.0), max: Some(100.0) };
+
+        assert_eq!(scaling.apply(Value::Unsigned(1000)), Value::Float(1000.0 * 0.1 - 40.0));
+        assert_eq!(scaling.apply(Value::Unsigned(0)), Value::Float(-20.0));
+        assert_eq!(scaling.apply(Value::Unsigned(65535)), Value::Float(100.0));
+        assert_eq!(scaling.apply(Value::Signed(-100)), Value::Float(-20.0));
+        assert_eq!(scaling.apply(Value::Text("n/a".into())), Value::Text("n/a".into()));
+
+        // unbounded side stays open
+        let open = Scaling { gain: 2.0, offset: 0.0, min: None, max: Some(10.0) };
+        assert_eq!(open.apply(Value::Signed(-100)), Value::Float(-200.0));
+        assert_eq!(open.apply(Value::Float(f64::INFINITY)), Value::Float(10.0));
+    }
+
+    #[test]
+    fn test_scaled_register_keeps_raw_for_audit() {
+        let mut map = RegisterMap::new(Unmapped::Drop);
+        map.include_raw = true;
+        map.insert(key(0), scaled("pressure", 0.01, 0.0, Some(0.0), Some(16.0))).unwrap();
+        map.insert(key(1), Tag { unit: Some("m3/h".to_string()), ..typed("flow", DataType::Float32, ByteOrder::Abcd) }).unwrap();
+
+        let registers = map.registers(1, FunctionCode::ReadHoldingRegisters, Some(0), &[250, 0x42F6, 0xE979]);
+
+        assert_eq!(registers[0].value, Value::Float(250.0 * 0.01));
+        assert_eq!(registers[0].raw, Some(vec![250]));
+        assert_eq!(registers[1].value, Value::Float(123.456f32 as f64));
+        assert_eq!(registers[1].unit.as_deref(), Some("m3/h"));
+        assert_eq!(registers[1].raw, Some(vec![0x42F6, 0xE979]));
+
+        map.include_raw = false;
+        let registers = map.registers(1, FunctionCode::ReadHoldingRegisters, Some(0), &[250]);
+        assert_eq!(registers[0].raw, None);
+    }
+
+    #[test]
+    fn test_insert_rejects_bad_scaling() {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+
+        assert!(map.insert(key(0), scaled("nan_gain", f64::NAN, 0.0, None, None)).is_err());
+        assert!(map.insert(key(0), scaled("inf_offset", 1.0, f64::INFINITY, None, None)).is_err());
+        assert!(map.insert(key(0), scaled("inverted", 1.0, 0.0, Some(10.0), Some(0.0))).is_err());
+        assert!(map.insert(key(0), scaled("nan_limit", 1.0, 0.0, Some(f64::NAN), None)).is_err());
+        let text = Tag { scaling: Some(Scaling { gain: 1.0, offset: 0.0, min: None, max: None }), ..typed("name", DataType::Ascii(2), ByteOrder::Abcd) };
+        assert!(map.insert(key(0), text).is_err());
+        assert!(map.insert(key(0), scaled("ok", -1.0, 5.0, Some(0.0), Some(0.0))).is_ok());
+    }
+
+    #[test]
+    fn test_status_bits_alongside_or_instead_of_word() {
+        let mut map = RegisterMap::new(Unmapped::Drop);
+        map.insert(key(40), status_word("pump_status", false)).unwrap();
+        map.insert(key(41), status_word("valve_status", true)).unwrap();
+
+        let words = [0x0008, 0x0001];
+        let registers = map.registers(1, FunctionCode::ReadHoldingRegisters, Some(40), &words);
+        assert_eq!(registers.len(), 1);
+        assert_eq!(registers[0].label.as_deref(), Some("pump_status"));
+
+        let bits = map.status_bits(1, FunctionCode::ReadHoldingRegisters, Some(40), &words);
+        let named: Vec<_> = bits.iter().map(|b| (b.address, b.label.as_str(), b.value)).collect();
+        assert_eq!(
+            named,
+            vec![(40, "running", false), (40, "pump_running", true), (41, "running", true), (41, "pump_running", false)]
+        );
+
+        assert!(map.status_bits(1, FunctionCode::ReadHoldingRegisters, None, &words).is_empty());
+        assert!(map.status_bits(1, FunctionCode::ReadInputRegisters, Some(40), &words).is_empty());
+    }
+
+    #[test]
+    fn test_status_bits_need_a_plain_word() {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+
+        let float = Tag { data_type: DataType::Float32, ..status_word("float", false) };
+        assert!(map.insert(key(0), float).is_err());
+        let scaled = Tag { scaling: Some(Scaling { gain: 2.0, offset: 0.0, min: None, max: None }), ..status_word("scaled", false) };
+        assert!(map.insert(key(0), scaled).is_err());
+        let coil = PointKey { table: Table::Coil, ..key(0) };
+        assert!(map.insert(coil, status_word("coil", false)).is_err());
+        assert!(map.insert(key(0), status_word("word", false)).is_ok());
+    }
+}
@@ -0,0 +1,375 @@
+// guest/src/report.rs
+// report-by-exception: at fast poll rates most frames repeat the values
+// already published, so points on a tag with a deadband are only published
+// once they move past the deadband from the value last published for them.
+// booleans and text have no magnitude and publish on any change. a maximum
+// silence interval republishes unchanged points so subscribers can tell a
+// steady value from a dead gateway. admitted values only count as published
+// once the publish succeeds: until then they are staged, and a failed
+// publish discards them so the broker is not left with a stale value.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::modbus::datatype::Value;
+use crate::mqtt::payload::{Bit, Register, StatusBit};
+use crate::register_map::{PointKey, RegisterMap, Table};
+
+/// how far a numeric point must move before it is published again
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Deadband {
+    Absolute(f64),  // in engineering units, i.e. after scaling
+    Percent(f64),   // of the last published value
+}
+
+impl Deadband {
+    /// reject negative and non-finite widths
+    pub fn validate(&self) -> Result<(), String> {
+        let (kind, width) = match *self {
+            Self::Absolute(width) => ("absolute", width),
+            Self::Percent(width) => ("percent", width),
+        };
+        if !width.is_finite() || width < 0.0 {
+            return Err(format!("{} deadband must be finite and non-negative", kind));
+        }
+        Ok(())
+    }
+
+    /// whether `value` has moved strictly past the deadband around `last`.
+    /// a percent deadband around zero publishes any change.
+    pub fn exceeded(&self, last: f64, value: f64) -> bool {
+        if last == value {
+            return false;
+        }
+        let threshold = match *self {
+            Self::Absolute(width) => width,
+            Self::Percent(width) => last.abs() * width / 100.0,
+        };
+        // nan never falls inside the deadband
+        !matches!((value - last).abs().partial_cmp(&threshold), Some(Ordering::Less | Ordering::Equal))
+    }
+
+    /// whether a point moved from `last` to `value`
+    fn changed(&self, last: &Value, value: &Value) -> bool {
+        match (last.as_f64(), value.as_f64()) {
+            (Some(last), Some(value)) => self.exceeded(last, value),
+            _ => last != value,
+        }
+    }
+}
+
+/// one published point - status bits share their word's point
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ReportKey {
+    point: PointKey,
+    bit: Option<u8>,
+}
+
+impl ReportKey {
+    /// coils, discrete inputs and status bits - compared by equality,
+    /// whatever the width of the deadband they carry
+    fn is_boolean(&self) -> bool {
+        self.bit.is_some() || matches!(self.point.table, Table::Coil | Table::DiscreteInput)
+    }
+}
+
+/// the value last published for a point, and when
+#[derive(Debug)]
+struct Reported {
+    value: Value,
+    at_ms: u64,
+}
+
+/// last published value of every point reported by exception.
+/// bounded by the register map - only tags with a deadband are tracked.
+#[derive(Debug, Default)]
+pub struct ExceptionReporter {
+    last: HashMap<ReportKey, Reported>,
+    staged: Vec<(ReportKey, Reported)>,  // admitted, awaiting the publish outcome
+}
+
+impl ExceptionReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// drop tagged registers that stayed inside their deadband,
+    /// returning how many were suppressed
+    pub fn retain_registers(
+        &mut self,
+        map: &RegisterMap,
+        unit_id: u8,
+        table: Table,
+        registers: &mut Vec<Register>,
+        now_ms: u64,
+    ) -> u64 {
+        let before = registers.len();
+        registers.retain(|r| {
+            // unlabelled registers have no tag, or no real address
+            if r.label.is_none() {
+                return true;
+            }
+            let point = PointKey { unit_id, table, address: r.address };
+            self.admit(map, ReportKey { point, bit: None }, &r.value, now_ms)
+        });
+        (before - registers.len()) as u64
+    }
+
+    /// drop tagged coils and discrete inputs that haven't changed
+    pub fn retain_bits(&mut self, map: &RegisterMap, unit_id: u8, table: Table, bits: &mut Vec<Bit>, now_ms: u64) -> u64 {
+        let before = bits.len();
+        bits.retain(|b| {
+            if b.label.is_none() {
+                return true;
+            }
+            let point = PointKey { unit_id, table, address: b.address };
+            self.admit(map, ReportKey { point, bit: None }, &Value::Unsigned(b.value as u64), now_ms)
+        });
+        (before - bits.len()) as u64
+    }
+
+    /// drop named status bits that haven't changed, when their word's
+    /// tag has a deadband
+    pub fn retain_status_bits(
+        &mut self,
+        map: &RegisterMap,
+        unit_id: u8,
+        table: Table,
+        bits: &mut Vec<StatusBit>,
+        now_ms: u64,
+    ) -> u64 {
+        let before = bits.len();
+        bits.retain(|b| {
+            let point = PointKey { unit_id, table, address: b.address };
+            self.admit(map, ReportKey { point, bit: Some(b.bit) }, &Value::Unsigned(b.value as u64), now_ms)
+        });
+        (before - bits.len()) as u64
+    }
+
+    /// record the points admitted since the last commit or discard as
+    /// published - call once their publish succeeded
+    pub fn commit(&mut self) {
+        for (key, reported) in self.staged.drain(..) {
+            self.last.insert(key, reported);
+        }
+    }
+
+    /// forget the points admitted since the last commit or discard, so a
+    /// failed publish is retried on the next read instead of suppressed
+    pub fn discard(&mut self) {
+        self.staged.clear();
+    }
+
+    /// whether to publish `value`, staging it if so. points without a
+    /// deadband always publish and are never stored.
+    fn admit(&mut self, map: &RegisterMap, key: ReportKey, value: &Value, now_ms: u64) -> bool {
+        let Some(deadband) = map.get(&key.point).and_then(|t| t.deadband) else {
+            return true;
+        };
+        let publish = match self.last.get(&key) {
+            None => true,
+            Some(last) => {
+                // a clock that went backwards also forces a publish, rather
+                // than silencing the point until it catches up
+                let silent = map
+                    .max_silence_ms
+                    .is_some_and(|max| now_ms < last.at_ms || now_ms - last.at_ms >= max);
+                let changed = if key.is_boolean() {
+                    last.value != *value
+                } else {
+                    deadband.changed(&last.value, value)
+                };
+                silent || changed
+            }
+        };
+        if publish {
+            self.staged.push((key, Reported { value: value.clone(), at_ms: now_ms }));
+        }
+        publish
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::register_map::{Tag, Unmapped};
+
+    fn key(address: u16) -> PointKey {
+        PointKey {
+            unit_id: 1,
+            table: Table::HoldingRegister,
+            address,
+        }
+    }
+
+    fn tag(name: &str, deadband: Option<Deadband>) -> Tag {
+        Tag { deadband, ..Tag::named(name) }
+    }
+
+    fn register(address: u16, value: u64, label: Option<&str>) -> Register {
+        Register {
+            address,
+            value: Value::Unsigned(value),
+            data_type: None,
+            label: label.map(str::to_string),
+            description: None,
+            unit: None,
+            raw: None,
+        }
+    }
+
+    /// map with "level" at 10 (absolute 5) and "flow" at 11 (no deadband)
+    fn map(max_silence_ms: Option<u64>) -> RegisterMap {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+        map.max_silence_ms = max_silence_ms;
+        map.insert(key(10), tag("level", Some(Deadband::Absolute(5.0)))).unwrap();
+        map.insert(key(11), tag("flow", None)).unwrap();
+        map
+    }
+
+    /// addresses of the registers published for one frame
+    fn published(reporter: &mut ExceptionReporter, map: &RegisterMap, level: u64, now_ms: u64) -> Vec<u16> {
+        let mut registers = vec![
+            register(10, level, Some("level")),
+            register(11, 7, Some("flow")),
+            register(12, 7, None),
+        ];
+        reporter.retain_registers(map, 1, Table::HoldingRegister, &mut registers, now_ms);
+        reporter.commit();
+        registers.iter().map(|r| r.address).collect()
+    }
+
+    #[test]
+    fn test_deadband_exceeded() {
+        let absolute = Deadband::Absolute(0.5);
+        assert!(!absolute.exceeded(10.0, 10.5));
+        assert!(absolute.exceeded(10.0, 10.6));
+        assert!(absolute.exceeded(10.0, 9.4));
+
+        let percent = Deadband::Percent(10.0);
+        assert!(!percent.exceeded(200.0, 220.0));
+        assert!(percent.exceeded(200.0, 221.0));
+        assert!(percent.exceeded(-200.0, -179.0));
+        // no magnitude to take a percentage of
+        assert!(percent.exceeded(0.0, 0.001));
+
+        assert!(Deadband::Absolute(0.0).exceeded(1.0, 1.000001));
+        assert!(absolute.exceeded(1.0, f64::NAN));
+        assert!(!absolute.exceeded(f64::INFINITY, f64::INFINITY));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Deadband::Absolute(0.0).validate(), Ok(()));
+        assert_eq!(Deadband::Percent(2.5).validate(), Ok(()));
+        assert!(Deadband::Absolute(-1.0).validate().is_err());
+        assert!(Deadband::Percent(f64::NAN).validate().is_err());
+        assert!(Deadband::Absolute(f64::INFINITY).validate().is_err());
+    }
+
+    #[test]
+    fn test_suppresses_moves_inside_deadband() {
+        let map = map(None);
+        let mut reporter = ExceptionReporter::new();
+
+        // first sighting always publishes
+        assert_eq!(published(&mut reporter, &map, 100, 0), vec![10, 11, 12]);
+        // untagged and deadband-less points publish every frame
+        assert_eq!(published(&mut reporter, &map, 104, 100), vec![11, 12]);
+        assert_eq!(published(&mut reporter, &map, 105, 200), vec![11, 12]);
+        assert_eq!(published(&mut reporter, &map, 106, 300), vec![10, 11, 12]);
+        // slow drift is measured from the last published value
+        assert_eq!(published(&mut reporter, &map, 110, 400), vec![11, 12]);
+        assert_eq!(published(&mut reporter, &map, 112, 500), vec![10, 11, 12]);
+    }
+
+    #[test]
+    fn test_max_silence_republishes() {
+        let map = map(Some(1000));
+        let mut reporter = ExceptionReporter::new();
+
+        assert_eq!(published(&mut reporter, &map, 100, 5000), vec![10, 11, 12]);
+        assert_eq!(published(&mut reporter, &map, 100, 5999), vec![11, 12]);
+        assert_eq!(published(&mut reporter, &map, 100, 6000), vec![10, 11, 12]);
+        assert_eq!(published(&mut reporter, &map, 100, 6500), vec![11, 12]);
+        // clock stepped backwards
+        assert_eq!(published(&mut reporter, &map, 100, 1000), vec![10, 11, 12]);
+        assert_eq!(published(&mut reporter, &map, 100, 1001), vec![11, 12]);
+    }
+
+    #[test]
+    fn test_counts_suppressed() {
+        let map = map(None);
+        let mut reporter = ExceptionReporter::new();
+        let frame = || vec![register(10, 100, Some("level")), register(11, 7, Some("flow"))];
+
+        assert_eq!(reporter.retain_registers(&map, 1, Table::HoldingRegister, &mut frame(), 0), 0);
+        reporter.commit();
+        assert_eq!(reporter.retain_registers(&map, 1, Table::HoldingRegister, &mut frame(), 0), 1);
+        // the same address in another table is another point
+        assert_eq!(reporter.retain_registers(&map, 1, Table::InputRegister, &mut frame(), 0), 0);
+    }
+
+    #[test]
+    fn test_failed_publish_is_not_reported() {
+        let map = map(None);
+        let mut reporter = ExceptionReporter::new();
+
+        assert_eq!(published(&mut reporter, &map, 100, 0), vec![10, 11, 12]);
+        // the move past the deadband was admitted, but its publish failed
+        let mut registers = vec![register(10, 110, Some("level"))];
+        reporter.retain_registers(&map, 1, Table::HoldingRegister, &mut registers, 100);
+        assert_eq!(registers.len(), 1);
+        reporter.discard();
+        // so the unchanged value is still news to the broker
+        assert_eq!(published(&mut reporter, &map, 110, 200), vec![10, 11, 12]);
+        assert_eq!(published(&mut reporter, &map, 110, 300), vec![11, 12]);
+    }
+
+    #[test]
+    fn test_booleans_publish_on_change() {
+        // the deadband width means nothing to a boolean
+        for width in [0.0, 1.0, 10.0] {
+            let mut map = RegisterMap::new(Unmapped::PassThrough);
+            let coil = PointKey { table: Table::Coil, ..key(4) };
+            map.insert(coil, tag("pump_on", Some(Deadband::Absolute(width)))).unwrap();
+            let mut reporter = ExceptionReporter::new();
+            let mut observe = |value: bool| {
+                let mut bits = vec![Bit {
+                    address: 4,
+                    value,
+                    label: Some("pump_on".to_string()),
+                    description: None,
+                }];
+                reporter.retain_bits(&map, 1, Table::Coil, &mut bits, 0);
+                reporter.commit();
+                bits.len()
+            };
+
+            assert_eq!(observe(false), 1, "width {}", width);
+            assert_eq!(observe(false), 0, "width {}", width);
+            assert_eq!(observe(true), 1, "width {}", width);
+            assert_eq!(observe(true), 0, "width {}", width);
+            assert_eq!(observe(false), 1, "width {}", width);
+        }
+    }
+
+    #[test]
+    fn test_status_bits_ignore_word_deadband() {
+        let mut map = RegisterMap::new(Unmapped::PassThrough);
+        map.insert(key(20), tag("status", Some(Deadband::Absolute(10.0)))).unwrap();
+        let mut reporter = ExceptionReporter::new();
+        let mut observe = |value: bool| {
+            let mut bits = vec![StatusBit { address: 20, bit: 3, label: "pump_running".to_string(), value }];
+            reporter.retain_status_bits(&map, 1, Table::HoldingRegister, &mut bits, 0);
+            reporter.commit();
+            bits.len()
+        };
+
+        assert_eq!(observe(false), 1);
+        assert_eq!(observe(false), 0);
+        assert_eq!(observe(true), 1);
+        assert_eq!(observe(true), 0);
+        assert_eq!(observe(false), 1);
+    }
+}
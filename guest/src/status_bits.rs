@@ -0,0 +1,172 @@
+// guest/src/status_bits.rs
+// named bits packed into 16-bit status words.
+// many plcs pack alarm and state flags into one holding register; the
+// register map names the bits so each is published as its own boolean, and
+// the change detector remembers the last value of every named bit so a single
+// flip becomes a clear event instead of a changed integer in a telemetry feed.
+
+use std::collections::HashMap;
+
+use crate::mqtt::payload::StatusBit;
+use crate::register_map::Table;
+
+/// one named bit of a status word
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitField {
+    pub bit: u8,       // 0 = least significant
+    pub name: String,
+}
+
+/// the named bits of one status word
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusBits {
+    pub fields: Vec<BitField>,
+    pub replace_word: bool,  // publish only the bits, not the word itself
+}
+
+impl StatusBits {
+    /// reject bit numbers past 15 and duplicate or empty names
+    pub fn validate(&self) -> Result<(), String> {
+        if self.fields.is_empty() {
+            return Err("status word names no bits".to_string());
+        }
+        for (i, field) in self.fields.iter().enumerate() {
+            if field.bit > 15 {
+                return Err(format!("bit '{}' is {} - must be 0-15", field.name, field.bit));
+            }
+            if field.name.is_empty() {
+                return Err(format!("bit {} has an empty name", field.bit));
+            }
+            let earlier = &self.fields[..i];
+            if earlier.iter().any(|f| f.bit == field.bit || f.name == field.name) {
+                return Err(format!("bit '{}' ({}) is named twice", field.name, field.bit));
+            }
+        }
+        Ok(())
+    }
+
+    /// the named bits of `word` read at `address`
+    pub fn extract(&self, address: u16, word: u16) -> impl Iterator<Item = StatusBit> + '_ {
+        self.fields.iter().map(move |field| StatusBit {
+            address,
+            bit: field.bit,
+            label: field.name.clone(),
+            value: (word >> field.bit) & 1 == 1,
+        })
+    }
+}
+
+/// identifies one bit of one status word on one slave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct BitKey {
+    unit_id: u8,
+    table: Table,
+    address: u16,
+    bit: u8,
+}
+
+/// last seen value of every named bit.
+/// bounded by the register map - only configured bits are ever tracked.
+#[derive(Debug, Default)]
+pub struct ChangeDetector {
+    last: HashMap<BitKey, bool>,
+}
+
+impl ChangeDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// record the bits of one response, returning those that flipped.
+    /// the first sighting of a bit is its initial state, not a change.
+    pub fn observe(&mut self, unit_id: u8, table: Table, bits: &[StatusBit]) -> Vec<StatusBit> {
+        bits.iter()
+            .filter(|b| {
+                let key = BitKey { unit_id, table, address: b.address, bit: b.bit };
+                self.last.insert(key, b.value).is_some_and(|previous| previous != b.value)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status() -> StatusBits {
+        StatusBits {
+            fields: vec![
+                BitField { bit: 0, name: "running".to_string() },
+                BitField { bit: 3, name: "pump_running".to_string() },
+                BitField { bit: 15, name: "fault".to_string() },
+            ],
+            replace_word: false,
+        }
+    }
+
+    #[test]
+    fn test_extract() {
+        let bits: Vec<_> = status().extract(40, 0b1000_0000_0000_1000).collect();
+
+        assert_eq!(bits.len(), 3);
+        assert_eq!((bits[0].label.as_str(), bits[0].value), ("running", false));
+        assert_eq!((bits[1].label.as_str(), bits[1].value), ("pump_running", true));
+        assert_eq!((bits[2].label.as_str(), bits[2].value), ("fault", true));
+        assert!(bits.iter().all(|b| b.address == 40));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(status().validate(), Ok(()));
+
+        let mut bad = status();
+        bad.fields.push(BitField { bit: 16, name: "overflow".to_string() });
+        assert!(bad.validate().is_err());
+
+        let mut bad = status();
+        bad.fields.push(BitField { bit: 3, name: "alias".to_string() });
+        assert!(bad.validate().is_err());
+
+        let mut bad = status();
+        bad.fields.push(BitField { bit: 4, name: "running".to_string() });
+        assert!(bad.validate().is_err());
+
+        let empty = StatusBits { fields: Vec::new(), replace_word: true };
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_single_bit_flip_is_one_change() {
+        let status = status();
+        let mut detector = ChangeDetector::new();
+        let observe = |detector: &mut ChangeDetector, word: u16| {
+            let bits: Vec<_> = status.extract(40, word).collect();
+            detector.observe(1, Table::HoldingRegister, &bits)
+        };
+
+        // initial state is not a change
+        assert!(observe(&mut detector, 0x0001).is_empty());
+        assert!(observe(&mut detector, 0x0001).is_empty());
+
+        let changes = observe(&mut detector, 0x0009);
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].label.as_str(), changes[0].value), ("pump_running", true));
+
+        // unnamed bits never produce events
+        assert!(observe(&mut detector, 0x0F09).is_empty());
+    }
+
+    #[test]
+    fn test_changes_are_tracked_per_unit_and_table() {
+        let status = status();
+        let mut detector = ChangeDetector::new();
+        let on: Vec<_> = status.extract(40, 0x0001).collect();
+        let off: Vec<_> = status.extract(40, 0x0000).collect();
+
+        detector.observe(1, Table::HoldingRegister, &on);
+        assert!(detector.observe(2, Table::HoldingRegister, &off).is_empty());
+        assert!(detector.observe(1, Table::InputRegister, &off).is_empty());
+        assert_eq!(detector.observe(1, Table::HoldingRegister, &off).len(), 1);
+    }
+}
@@ -0,0 +1,68 @@
+// guest/src/timestamp.rs
+// rfc 3339 timestamps for payloads, e.g. "2026-01-05T14:03:07.250Z".
+// always utc with exactly three fractional digits, so timestamps sort as
+// strings. the calendar conversion is howard hinnant's civil-from-days,
+// which needs no tables or loops.
+
+/// the last millisecond with a four-digit year, 9999-12-31T23:59:59.999Z
+pub const MAX_MS: u64 = 253_402_300_799_999;
+
+/// milliseconds since the unix epoch as an rfc 3339 utc timestamp.
+/// later times clamp to MAX_MS - rfc 3339 has no five-digit years.
+pub fn rfc3339(epoch_ms: u64) -> String {
+    let epoch_ms = epoch_ms.min(MAX_MS);
+    let millis = epoch_ms % 1000;
+    let secs = epoch_ms / 1000;
+    let (hour, minute, second) = (secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hour, minute, second, millis
+    )
+}
+
+/// (year, month, day) of the day `days` after 1970-01-01
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // shift the epoch to 0000-03-01, so leap days end each 400-year era
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;  // month, counted from march
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// milliseconds since the unix epoch from whole seconds plus nanoseconds,
+/// as wasi clocks report them
+pub fn epoch_ms(seconds: u64, nanoseconds: u32) -> u64 {
+    seconds.saturating_mul(1000).saturating_add(u64::from(nanoseconds / 1_000_000))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(rfc3339(1_767_571_200_000), "2026-01-05T00:00:00.000Z");
+        assert_eq!(rfc3339(1_767_625_387_250), "2026-01-05T15:03:07.250Z");
+        // leap days, including the 400-year exception
+        assert_eq!(rfc3339(951_782_400_000), "2000-02-29T00:00:00.000Z");
+        assert_eq!(rfc3339(1_709_251_199_999), "2024-02-29T23:59:59.999Z");
+        assert_eq!(rfc3339(4_107_542_400_000), "2100-03-01T00:00:00.000Z");
+        assert_eq!(rfc3339(MAX_MS), "9999-12-31T23:59:59.999Z");
+        assert_eq!(rfc3339(MAX_MS + 1), "9999-12-31T23:59:59.999Z");
+        assert_eq!(rfc3339(u64::MAX), "9999-12-31T23:59:59.999Z");
+    }
+
+    #[test]
+    fn test_epoch_ms() {
+        assert_eq!(epoch_ms(1_767_571_200, 999_999_999), 1_767_571_200_999);
+        assert_eq!(epoch_ms(u64::MAX, 0), u64::MAX);
+    }
+}
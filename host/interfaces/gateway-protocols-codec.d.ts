@@ -0,0 +1,3 @@
+/** @module Interface gateway:protocols/codec **/
+export function encodeReadRequest(transactionId: number, unitId: number, function_: number, startAddress: number, quantity: number): Uint8Array;
+export function encodeReadResponse(transactionId: number, unitId: number, function_: number, registers: Uint16Array): Uint8Array;
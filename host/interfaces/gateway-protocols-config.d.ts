@@ -0,0 +1,196 @@
+/** @module Interface gateway:protocols/config **/
+export function getFunctionPolicy(): FunctionPolicy;
+export function getPollSchedule(): PollSchedule;
+export function getRegisterMap(): RegisterMap;
+export function getTelemetryFormat(): TelemetryFormat;
+export function getPublishSettings(): PublishSettings;
+export function getTopicTemplates(): TopicTemplates;
+/**
+ * # Variants
+ * 
+ * ## `"allow"`
+ * 
+ * ## `"deny"`
+ */
+export type PolicyAction = 'allow' | 'deny';
+export interface AddressRange {
+  start: number,
+  end: number,
+}
+export interface PolicyRule {
+  name: string,
+  unitId?: number,
+  functionCode?: number,
+  addressRange?: AddressRange,
+  action: PolicyAction,
+}
+export interface FunctionPolicy {
+  rules: Array<PolicyRule>,
+  defaultAction: PolicyAction,
+}
+export interface PollEntry {
+  name: string,
+  unitId: number,
+  functionCode: number,
+  startAddress: number,
+  quantity: number,
+  intervalMs: number,
+}
+export interface PollSchedule {
+  entries: Array<PollEntry>,
+  timeoutMs: number,
+}
+/**
+ * # Variants
+ * 
+ * ## `"coil"`
+ * 
+ * ## `"discrete-input"`
+ * 
+ * ## `"input-register"`
+ * 
+ * ## `"holding-register"`
+ */
+export type PointTable = 'coil' | 'discrete-input' | 'input-register' | 'holding-register';
+export type DataType = DataTypeUint16 | DataTypeInt16 | DataTypeUint32 | DataTypeInt32 | DataTypeUint64 | DataTypeInt64 | DataTypeFloat32 | DataTypeFloat64 | DataTypeBcd16 | DataTypeBcd32 | DataTypeAscii;
+export interface DataTypeUint16 {
+  tag: 'uint16',
+}
+export interface DataTypeInt16 {
+  tag: 'int16',
+}
+export interface DataTypeUint32 {
+  tag: 'uint32',
+}
+export interface DataTypeInt32 {
+  tag: 'int32',
+}
+export interface DataTypeUint64 {
+  tag: 'uint64',
+}
+export interface DataTypeInt64 {
+  tag: 'int64',
+}
+export interface DataTypeFloat32 {
+  tag: 'float32',
+}
+export interface DataTypeFloat64 {
+  tag: 'float64',
+}
+export interface DataTypeBcd16 {
+  tag: 'bcd16',
+}
+export interface DataTypeBcd32 {
+  tag: 'bcd32',
+}
+export interface DataTypeAscii {
+  tag: 'ascii',
+  val: number,
+}
+/**
+ * # Variants
+ * 
+ * ## `"abcd"`
+ * 
+ * ## `"cdab"`
+ * 
+ * ## `"badc"`
+ * 
+ * ## `"dcba"`
+ */
+export type ByteOrder = 'abcd' | 'cdab' | 'badc' | 'dcba';
+export interface Scaling {
+  gain: number,
+  offset: number,
+  min?: number,
+  max?: number,
+}
+export interface BitField {
+  bit: number,
+  name: string,
+}
+export interface StatusBits {
+  fields: Array<BitField>,
+  replaceWord: boolean,
+}
+export type Deadband = DeadbandAbsolute | DeadbandPercent;
+export interface DeadbandAbsolute {
+  tag: 'absolute',
+  val: number,
+}
+export interface DeadbandPercent {
+  tag: 'percent',
+  val: number,
+}
+export interface AlarmLimits {
+  hihi?: number,
+  hi?: number,
+  lo?: number,
+  lolo?: number,
+  hysteresis: number,
+}
+export interface OutlierCheck {
+  window: number,
+  sigma: number,
+}
+export interface AnomalyChecks {
+  maxRate?: number,
+  frozenAfterMs?: number,
+  outlier?: OutlierCheck,
+}
+export interface RegisterTag {
+  unitId: number,
+  table: PointTable,
+  address: number,
+  tag: string,
+  description?: string,
+  unit?: string,
+  dataType?: DataType,
+  byteOrder?: ByteOrder,
+  scaling?: Scaling,
+  statusBits?: StatusBits,
+  deadband?: Deadband,
+  limits?: AlarmLimits,
+  anomaly?: AnomalyChecks,
+}
+/**
+ * # Variants
+ * 
+ * ## `"pass-through"`
+ * 
+ * ## `"drop"`
+ */
+export type UnmappedPoints = 'pass-through' | 'drop';
+export interface RegisterMap {
+  tags: Array<RegisterTag>,
+  unmapped: UnmappedPoints,
+  includeRaw: boolean,
+  maxSilenceMs?: number,
+}
+export interface SparkplugNode {
+  groupId: string,
+  edgeNodeId: string,
+}
+export type TelemetryFormat = TelemetryFormatJson | TelemetryFormatSparkplugB;
+export interface TelemetryFormatJson {
+  tag: 'json',
+}
+export interface TelemetryFormatSparkplugB {
+  tag: 'sparkplug-b',
+  val: SparkplugNode,
+}
+export interface PublishSettings {
+  gatewayId: string,
+  telemetryExpirySecs?: number,
+}
+export interface TopicTemplates {
+  site: string,
+  area: string,
+  telemetry?: string,
+  events?: string,
+  alarms?: string,
+  alarmState?: string,
+  security?: string,
+  status?: string,
+  devices?: string,
+}
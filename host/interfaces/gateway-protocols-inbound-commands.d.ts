@@ -0,0 +1,45 @@
+/** @module Interface gateway:protocols/inbound-commands **/
+export function nextCommand(): Command | undefined;
+/**
+ * # Variants
+ * 
+ * ## `"lolo"`
+ * 
+ * ## `"lo"`
+ * 
+ * ## `"hi"`
+ * 
+ * ## `"hihi"`
+ */
+export type AlarmLevel = 'lolo' | 'lo' | 'hi' | 'hihi';
+export type AlarmAction = AlarmActionAcknowledge | AlarmActionShelve | AlarmActionUnshelve | AlarmActionSuppress | AlarmActionUnsuppress;
+export interface AlarmActionAcknowledge {
+  tag: 'acknowledge',
+}
+export interface AlarmActionShelve {
+  tag: 'shelve',
+  val: number,
+}
+export interface AlarmActionUnshelve {
+  tag: 'unshelve',
+}
+export interface AlarmActionSuppress {
+  tag: 'suppress',
+}
+export interface AlarmActionUnsuppress {
+  tag: 'unsuppress',
+}
+export interface AlarmCommand {
+  unitId: number,
+  tag: string,
+  level: AlarmLevel,
+  action: AlarmAction,
+}
+export type Command = CommandAlarm | CommandRebirth;
+export interface CommandAlarm {
+  tag: 'alarm',
+  val: AlarmCommand,
+}
+export interface CommandRebirth {
+  tag: 'rebirth',
+}
@@ -1,9 +1,96 @@
 /** @module Interface gateway:protocols/metrics **/
 export function getStats(): GatewayStats;
+export interface ExceptionCount {
+  code: number,
+  count: bigint,
+}
+export interface FrameFault {
+  offset: number,
+  prefix: Uint8Array,
+  detail: string,
+}
+export type GatewayError = GatewayErrorTruncatedHeader | GatewayErrorBadProtocolId | GatewayErrorBadLength | GatewayErrorBadChecksum | GatewayErrorBadFraming | GatewayErrorIllegalFunction | GatewayErrorByteCountMismatch | GatewayErrorMalformedPdu | GatewayErrorInvalidRequest | GatewayErrorResponseMismatch | GatewayErrorStreamResync | GatewayErrorReceiveFailure | GatewayErrorPublishFailure | GatewayErrorPollFailure | GatewayErrorInvalidConfig | GatewayErrorInvalidCommand;
+export interface GatewayErrorTruncatedHeader {
+  tag: 'truncated-header',
+  val: FrameFault,
+}
+export interface GatewayErrorBadProtocolId {
+  tag: 'bad-protocol-id',
+  val: FrameFault,
+}
+export interface GatewayErrorBadLength {
+  tag: 'bad-length',
+  val: FrameFault,
+}
+export interface GatewayErrorBadChecksum {
+  tag: 'bad-checksum',
+  val: FrameFault,
+}
+export interface GatewayErrorBadFraming {
+  tag: 'bad-framing',
+  val: FrameFault,
+}
+export interface GatewayErrorIllegalFunction {
+  tag: 'illegal-function',
+  val: FrameFault,
+}
+export interface GatewayErrorByteCountMismatch {
+  tag: 'byte-count-mismatch',
+  val: FrameFault,
+}
+export interface GatewayErrorMalformedPdu {
+  tag: 'malformed-pdu',
+  val: FrameFault,
+}
+export interface GatewayErrorInvalidRequest {
+  tag: 'invalid-request',
+  val: FrameFault,
+}
+export interface GatewayErrorResponseMismatch {
+  tag: 'response-mismatch',
+  val: FrameFault,
+}
+export interface GatewayErrorStreamResync {
+  tag: 'stream-resync',
+  val: bigint,
+}
+export interface GatewayErrorReceiveFailure {
+  tag: 'receive-failure',
+  val: string,
+}
+export interface GatewayErrorPublishFailure {
+  tag: 'publish-failure',
+  val: string,
+}
+export interface GatewayErrorPollFailure {
+  tag: 'poll-failure',
+  val: string,
+}
+export interface GatewayErrorInvalidConfig {
+  tag: 'invalid-config',
+  val: string,
+}
+export interface GatewayErrorInvalidCommand {
+  tag: 'invalid-command',
+  val: string,
+}
 export interface GatewayStats {
   framesProcessed: bigint,
   framesInvalid: bigint,
   bytesIn: bigint,
   bytesOut: bigint,
-  lastError?: string,
+  lastError?: GatewayError,
+  exceptionsReceived: bigint,
+  exceptionCounts: Array<ExceptionCount>,
+  responsesUnmatched: bigint,
+  responsesDuplicate: bigint,
+  streamBytesDiscarded: bigint,
+  framesDenied: bigint,
+  lastDenial?: string,
+  pollsSent: bigint,
+  pollFailures: bigint,
+  updatesSuppressed: bigint,
+  commandsApplied: bigint,
+  commandsRejected: bigint,
+  securityAnomalies: bigint,
 }
@@ -0,0 +1,4 @@
+/** @module Interface gateway:protocols/modbus-client **/
+export function transact(request: Uint8Array, timeoutMs: number): ReceivedFrame;
+export type ErrorCode = import('./gateway-protocols-modbus-source.js').ErrorCode;
+export type ReceivedFrame = import('./gateway-protocols-modbus-source.js').ReceivedFrame;
@@ -1,6 +1,29 @@
 /** @module Interface gateway:protocols/modbus-source **/
-export function receiveFrame(): Uint8Array;
+export function sourceFraming(): Framing;
+export function receiveFrame(): ReceivedFrame;
+export type Datetime = import('./wasi-clocks-wall-clock.js').Datetime;
 export interface ErrorCode {
   code: number,
   message: string,
 }
+/**
+ * # Variants
+ * 
+ * ## `"tcp"`
+ * 
+ * ## `"tcp-stream"`
+ * 
+ * ## `"rtu"`
+ * 
+ * ## `"ascii"`
+ */
+export type Framing = 'tcp' | 'tcp-stream' | 'rtu' | 'ascii';
+export interface FrameMeta {
+  sourceUri: string,
+  connectionId: string,
+  receivedAt: Datetime,
+}
+export interface ReceivedFrame {
+  data: Uint8Array,
+  meta: FrameMeta,
+}
@@ -1,6 +1,16 @@
 /** @module Interface gateway:protocols/mqtt-sink **/
-export function publish(topic: string, payload: string, qos: number): void;
+export function publish(topic: string, payload: string, options: PublishOptions): void;
+export function publishBinary(topic: string, payload: Uint8Array, options: PublishOptions): void;
+export function setWill(topic: string, payload: Uint8Array, options: PublishOptions): void;
 export interface ErrorCode {
   code: number,
   message: string,
 }
+export interface PublishOptions {
+  qos: number,
+  retain: boolean,
+  messageExpirySecs?: number,
+  contentType?: string,
+  correlationData?: Uint8Array,
+  userProperties: Array<[string, string]>,
+}
@@ -0,0 +1,3 @@
+/** @module Interface wasi:clocks/monotonic-clock@0.2.3 **/
+export function now(): Instant;
+export type Instant = bigint;
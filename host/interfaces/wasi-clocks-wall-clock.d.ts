@@ -1,4 +1,5 @@
 /** @module Interface wasi:clocks/wall-clock@0.2.3 **/
+export function now(): Datetime;
 export interface Datetime {
   seconds: bigint,
   nanoseconds: number,
@@ -1,4 +1,7 @@
 // world root:component/root
+export type * as GatewayProtocolsConfig from './interfaces/gateway-protocols-config.js'; // import gateway:protocols/config
+export type * as GatewayProtocolsInboundCommands from './interfaces/gateway-protocols-inbound-commands.js'; // import gateway:protocols/inbound-commands
+export type * as GatewayProtocolsModbusClient from './interfaces/gateway-protocols-modbus-client.js'; // import gateway:protocols/modbus-client
 export type * as GatewayProtocolsModbusSource from './interfaces/gateway-protocols-modbus-source.js'; // import gateway:protocols/modbus-source
 export type * as GatewayProtocolsMqttSink from './interfaces/gateway-protocols-mqtt-sink.js'; // import gateway:protocols/mqtt-sink
 export type * as WasiCliEnvironment023 from './interfaces/wasi-cli-environment.js'; // import wasi:cli/environment@0.2.3
@@ -6,10 +9,14 @@ export type * as WasiCliExit023 from './interfaces/wasi-cli-exit.js'; // import
 export type * as WasiCliStderr023 from './interfaces/wasi-cli-stderr.js'; // import wasi:cli/stderr@0.2.3
 export type * as WasiCliStdin023 from './interfaces/wasi-cli-stdin.js'; // import wasi:cli/stdin@0.2.3
 export type * as WasiCliStdout023 from './interfaces/wasi-cli-stdout.js'; // import wasi:cli/stdout@0.2.3
+export type * as WasiClocksMonotonicClock023 from './interfaces/wasi-clocks-monotonic-clock.js'; // import wasi:clocks/monotonic-clock@0.2.3
 export type * as WasiClocksWallClock023 from './interfaces/wasi-clocks-wall-clock.js'; // import wasi:clocks/wall-clock@0.2.3
 export type * as WasiFilesystemPreopens023 from './interfaces/wasi-filesystem-preopens.js'; // import wasi:filesystem/preopens@0.2.3
 export type * as WasiFilesystemTypes023 from './interfaces/wasi-filesystem-types.js'; // import wasi:filesystem/types@0.2.3
 export type * as WasiIoError023 from './interfaces/wasi-io-error.js'; // import wasi:io/error@0.2.3
 export type * as WasiIoStreams023 from './interfaces/wasi-io-streams.js'; // import wasi:io/streams@0.2.3
+export * as codec from './interfaces/gateway-protocols-codec.js'; // export gateway:protocols/codec
 export * as metrics from './interfaces/gateway-protocols-metrics.js'; // export gateway:protocols/metrics
 export function run(): void;
+export function trackRequest(frame: Uint8Array): void;
+export function pollDue(nowMs: bigint): void;
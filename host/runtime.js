@@ -47,6 +47,7 @@
 import fs from 'node:fs/promises';
 import { fileURLToPath } from 'node:url';
 import { dirname, join } from 'node:path';
+import { frameMeta } from './shim/modbus-source.js';
 
 const __filename = fileURLToPath(import.meta.url);
 const __dirname = dirname(__filename);
@@ -260,7 +261,9 @@ function vote(results) {
  * process a frame through the gateway with 2oo3 voting.
  * 
  * processing sequence:
- * 1. run frame through all 3 instances in parallel
+ * 1. run frame through all 3 instances in parallel - each receives the
+ *    same received-frame record, so the receive time in their payloads
+ *    matches and does not split the vote
  * 2. collect results (or trap errors) from each
  * 3. vote on results:
  *    - 3/3 agree: use result, no fault
@@ -268,9 +271,10 @@ function vote(results) {
  *    - 0/3 agree: critical error, reject frame
  * 
  * @param {Uint8Array} frame - the modbus frame to process
+ * @param {object} meta - frame-meta record: source uri, connection id, receive time
  * @returns {Object} processing result with voting details
  */
-export async function processFrame(frame) {
+export async function processFrame(frame, meta = frameMeta()) {
     if (!instancePool[0] || !instancePool[1] || !instancePool[2]) {
         throw new Error('Gateway not initialized - call createGateway() first');
     }
@@ -281,9 +285,11 @@ export async function processFrame(frame) {
     const results = await Promise.all(
         instancePool.map(async (instance, idx) => {
             try {
-                // actual wasm call depends on jco transpile output
-                // const result = instance.exports.run(frame);
-                const result = { success: true, instance: idx };
+                // the guest pulls the frame through modbus-source receive-frame
+                // and publishes via mqtt-sink publish(topic, payload, options).
+                // actual wasm call depends on jco transpile output:
+                // queueFrame(frame, meta); instance.run();
+                const result = { success: true, instance: idx, source: meta.sourceUri };
                 return result;
             } catch (error) {
                 // check if this is a wasm trap
@@ -0,0 +1,207 @@
+// host/shim/config.js
+// site configuration handed to the wasm guest.
+// provides the config interface; the guest reads it once on first use.
+
+// default: no rules, allow every function code the parser supports
+let functionPolicy = {
+    rules: [],
+    defaultAction: 'allow',
+};
+
+// default: empty scan list, the guest only handles frames the host pushes
+let pollSchedule = {
+    entries: [],
+    timeoutMs: 1000,
+};
+
+// default: no tags, every register published with its address only
+let registerMap = {
+    tags: [],
+    unmapped: 'pass-through',
+    includeRaw: false,
+    maxSilenceMs: undefined,
+};
+
+// default: json documents on the ics/ topics
+let telemetryFormat = { tag: 'json' };
+
+// default: no gateway id, telemetry never expires
+let publishSettings = {
+    gatewayId: '',
+    telemetryExpirySecs: undefined,
+};
+
+// default: the built-in ics/... topic for every message
+let topicTemplates = {
+    site: '',
+    area: '',
+    telemetry: undefined,
+    events: undefined,
+    alarms: undefined,
+    alarmState: undefined,
+    security: undefined,
+    status: undefined,
+    devices: undefined,
+};
+
+/**
+ * replace the function code policy.
+ * must be called before the guest processes its first frame.
+ * @param {{rules: Array, defaultAction: 'allow'|'deny'}} policy
+ *
+ * example - only holding registers, never on unit 7:
+ *   setFunctionPolicy({
+ *     rules: [
+ *       { name: 'no-unit-7', unitId: 7, action: 'deny' },
+ *       { name: 'holding', functionCode: 0x03, action: 'allow' },
+ *     ],
+ *     defaultAction: 'deny',
+ *   });
+ */
+export function setFunctionPolicy(policy) {
+    functionPolicy = policy;
+}
+
+/**
+ * wit interface implementation: get-function-policy
+ * jco maps option<T> to undefined, so unset rule fields can simply be omitted
+ */
+export function getFunctionPolicy() {
+    return functionPolicy;
+}
+
+/**
+ * replace the scan list the guest polls through modbus-client.
+ * must be called before the guest's first pollDue call.
+ * @param {{entries: Array, timeoutMs: number}} schedule
+ *
+ * example - holding registers every second, coils every 100 ms:
+ *   setPollSchedule({
+ *     entries: [
+ *       { name: 'setpoints', unitId: 1, functionCode: 0x03,
+ *         startAddress: 0, quantity: 10, intervalMs: 1000 },
+ *       { name: 'valves', unitId: 1, functionCode: 0x01,
+ *         startAddress: 0, quantity: 16, intervalMs: 100 },
+ *     ],
+ *     timeoutMs: 500,
+ *   });
+ */
+export function setPollSchedule(schedule) {
+    pollSchedule = schedule;
+}
+
+/**
+ * wit interface implementation: get-poll-schedule
+ */
+export function getPollSchedule() {
+    return pollSchedule;
+}
+
+/**
+ * replace the register map applied to telemetry.
+ * must be called before the guest processes its first frame.
+ * @param {{tags: Array, unmapped: 'pass-through'|'drop', includeRaw: boolean,
+ *          maxSilenceMs?: number}} map
+ *
+ * example - name three points on unit 1, publish nothing else:
+ *   setRegisterMap({
+ *     tags: [
+ *       { unitId: 1, table: 'holding-register', address: 0,
+ *         tag: 'boiler_temp', description: 'boiler outlet', unit: 'degC',
+ *         scaling: { gain: 0.1, offset: -40, min: -40, max: 150 },
+ *         // only publish moves of more than half a degree
+ *         deadband: { tag: 'absolute', val: 0.5 },
+ *         // alarms on ics/alarms/unit_1, clearing 2 degC inside the limit
+ *         limits: { hihi: 120, hi: 100, lo: 20, hysteresis: 2 } },
+ *       // ieee-754 float across registers 10-11, low word first
+ *       { unitId: 1, table: 'input-register', address: 10, tag: 'flow',
+ *         unit: 'm3/h', dataType: { tag: 'float32' }, byteOrder: 'cdab',
+ *         // injection checks - events on ics/security/unit_1
+ *         anomaly: { maxRate: 50, frozenAfterMs: 600000,
+ *           outlier: { window: 60, sigma: 4 } } },
+ *       { unitId: 1, table: 'coil', address: 4, tag: 'feed_pump_on' },
+ *       // packed flags - each named bit published, flips sent as events
+ *       { unitId: 1, table: 'holding-register', address: 40, tag: 'status',
+ *         statusBits: { replaceWord: true, fields: [
+ *           { bit: 0, name: 'running' }, { bit: 15, name: 'fault' } ] } },
+ *     ],
+ *     unmapped: 'drop',
+ *     includeRaw: true,
+ *     // republish deadbanded points at least once a minute
+ *     maxSilenceMs: 60000,
+ *   });
+ */
+export function setRegisterMap(map) {
+    registerMap = map;
+}
+
+/**
+ * wit interface implementation: get-register-map
+ */
+export function getRegisterMap() {
+    return registerMap;
+}
+
+/**
+ * choose how telemetry is encoded on the sink.
+ * must be called before the guest processes its first frame.
+ * @param {{tag: 'json'} | {tag: 'sparkplug-b', val: {groupId: string, edgeNodeId: string}}} format
+ *
+ * example - sparkplug b for ignition, units appear as devices unit_1, unit_2...
+ *   setTelemetryFormat({ tag: 'sparkplug-b',
+ *     val: { groupId: 'plant', edgeNodeId: 'gateway1' } });
+ */
+export function setTelemetryFormat(format) {
+    telemetryFormat = format;
+}
+
+/**
+ * wit interface implementation: get-telemetry-format
+ */
+export function getTelemetryFormat() {
+    return telemetryFormat;
+}
+
+/**
+ * replace the mqtt topic templates. unset topics keep their default.
+ * must be called before the guest processes its first frame.
+ * placeholders: {site}, {area}, {unit_id}, {function}, {tag}, {level}
+ * and {source}, {connection_id} from each frame's meta (not alarm state)
+ * @param {{site: string, area: string, telemetry?: string, events?: string,
+ *          alarms?: string, alarmState?: string, security?: string,
+ *          status?: string, devices?: string}} templates
+ *
+ * example - one telemetry message per tag under the site and area:
+ *   setTopicTemplates({ site: 'plant1', area: 'boilers',
+ *     telemetry: '{site}/{area}/{unit_id}/{function}/{tag}',
+ *     alarmState: '{site}/{area}/alarms/{unit_id}/{tag}/{level}' });
+ */
+export function setTopicTemplates(templates) {
+    topicTemplates = templates;
+}
+
+/**
+ * wit interface implementation: get-topic-templates
+ */
+export function getTopicTemplates() {
+    return topicTemplates;
+}
+
+/**
+ * replace the properties added to published messages.
+ * must be called before the guest processes its first frame.
+ * @param {{gatewayId: string, telemetryExpirySecs?: number}} settings
+ *
+ * example - identify this gateway, drop telemetry older than a minute:
+ *   setPublishSettings({ gatewayId: 'gw-north-01', telemetryExpirySecs: 60 });
+ */
+export function setPublishSettings(settings) {
+    publishSettings = settings;
+}
+
+/**
+ * wit interface implementation: get-publish-settings
+ */
+export function getPublishSettings() {
+    return publishSettings;
+}
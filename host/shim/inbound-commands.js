@@ -0,0 +1,60 @@
+// host/shim/inbound-commands.js
+// mock command queue standing in for an mqtt subscription.
+// provides the inbound-commands interface to the wasm guest - the host
+// queues operator commands as they arrive and the guest drains them.
+
+// commands waiting for the guest, oldest first
+let queue = [];
+
+/**
+ * queue an operator command, as if it arrived on the command topic
+ * @param {object} command - e.g. { tag: 'alarm', val: { unitId: 1,
+ *     tag: 'boiler_temp', level: 'hi', action: { tag: 'acknowledge' } } }
+ */
+export function pushCommand(command) {
+    queue.push(command);
+}
+
+/**
+ * acknowledge one alarm - shorthand for pushCommand
+ * @param {number} unitId - modbus slave address
+ * @param {string} tag - tag name from the register map
+ * @param {'lolo'|'lo'|'hi'|'hihi'} level - which limit
+ */
+export function acknowledge(unitId, tag, level) {
+    pushCommand({ tag: 'alarm', val: { unitId, tag, level, action: { tag: 'acknowledge' } } });
+}
+
+/**
+ * shelve one alarm for a while - shorthand for pushCommand
+ * @param {number} unitId - modbus slave address
+ * @param {string} tag - tag name from the register map
+ * @param {'lolo'|'lo'|'hi'|'hihi'} level - which limit
+ * @param {number} durationMs - how long operators won't see it
+ */
+export function shelve(unitId, tag, level, durationMs) {
+    pushCommand({ tag: 'alarm', val: { unitId, tag, level, action: { tag: 'shelve', val: durationMs } } });
+}
+
+/**
+ * ask the sparkplug edge node to repeat its births - what a host application
+ * writing Node Control/Rebirth on the NCMD topic turns into
+ */
+export function rebirth() {
+    pushCommand({ tag: 'rebirth' });
+}
+
+/**
+ * drop every queued command
+ */
+export function clearCommands() {
+    queue = [];
+}
+
+/**
+ * wit interface implementation: next-command
+ * jco maps option<command> to the command or undefined
+ */
+export function nextCommand() {
+    return queue.shift();
+}
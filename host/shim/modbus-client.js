@@ -0,0 +1,89 @@
+// host/shim/modbus-client.js
+// mock modbus slave that answers the guest's scan-list reads.
+// provides the modbus-client interface to the wasm guest - the host only
+// moves bytes, the guest builds every request and parses every reply.
+
+import { frameMeta } from './modbus-source.js';
+
+// requests the guest sent, oldest first
+let sentRequests = [];
+// (request: Uint8Array, timeoutMs: number) => Uint8Array
+let responder = simulatedSlave;
+
+/**
+ * replace the mock slave, e.g. with a function that writes to a real
+ * socket or serial port. throw { code, message } to report a timeout.
+ * @param {function} fn - receives (request, timeoutMs), returns the reply frame
+ */
+export function setResponder(fn) {
+    responder = fn;
+}
+
+/**
+ * get all request frames the guest has sent
+ * @returns {Array<Uint8Array>}
+ */
+export function getSentRequests() {
+    return [...sentRequests];
+}
+
+/**
+ * clear the request log
+ */
+export function clearRequests() {
+    sentRequests = [];
+}
+
+/**
+ * wit interface implementation: transact
+ * jco expects the received-frame record directly; errors are thrown as
+ * exceptions. replies are described by the same meta as modbus-source.
+ */
+export function transact(request, timeoutMs) {
+    sentRequests.push(request);
+    const reply = responder(request, timeoutMs);
+    const data = reply instanceof Uint8Array ? reply : new Uint8Array(reply);
+    return { data, meta: frameMeta() };
+}
+
+/**
+ * default responder: answers modbus tcp reads 0x01-0x04 with values
+ * derived from the address, so published data is easy to eyeball
+ */
+function simulatedSlave(request) {
+    if (request.length !== 12) {
+        throw { code: 1, message: 'mock slave only speaks modbus tcp' };
+    }
+    const unitId = request[6];
+    const func = request[7];
+    const start = (request[8] << 8) | request[9];
+    const quantity = (request[10] << 8) | request[11];
+
+    let data;
+    if (func === 0x01 || func === 0x02) {
+        data = new Uint8Array(Math.ceil(quantity / 8));
+        for (let i = 0; i < quantity; i++) {
+            if ((start + i) % 2 === 0) data[i >> 3] |= 1 << (i & 7);
+        }
+    } else if (func === 0x03 || func === 0x04) {
+        data = new Uint8Array(quantity * 2);
+        for (let i = 0; i < quantity; i++) {
+            const value = (start + i) & 0xFFFF;
+            data[i * 2] = value >> 8;
+            data[i * 2 + 1] = value & 0xFF;
+        }
+    } else {
+        throw { code: 1, message: `mock slave does not answer function ${func}` };
+    }
+
+    const length = 3 + data.length;
+    return new Uint8Array([
+        request[0], request[1],
+        0x00, 0x00,
+        (length >> 8) & 0xFF, length & 0xFF,
+        unitId,
+        func,
+        data.length,
+        ...data,
+    ]);
+}
@@ -7,6 +7,12 @@ import crypto from 'crypto';
 // queue of frames to be returned 
 let frameQueue = [];
 let chaosMode = false;
+// 'tcp' (mbap), 'tcp-stream' (raw socket chunks), 'rtu' (rs-485 with crc16)
+// or 'ascii' (hex with lrc) - reported to the guest via sourceFraming
+let framing = 'tcp';
+// endpoint reported in each frame's meta, so payloads name their plc
+let sourceUri = 'modbus://plc:502';
+let connectionId = 'plc-1';
 
 // chaos attack vectors
 const CHAOS_ATTACKS = {
@@ -27,14 +33,59 @@ const CHAOS_ATTACKS = {
     randomGarbage: () => new Uint8Array(crypto.randomBytes(Math.floor(Math.random() * 300))),
 };
 
-export function queueFrame(frame) {
-    frameQueue.push(frame);
+/**
+ * queue a frame, stamped with where and when it arrived
+ * @param {Uint8Array} frame - the frame in the source's framing
+ * @param {object} meta - frame-meta record, defaults to the current source now
+ */
+export function queueFrame(frame, meta = frameMeta()) {
+    frameQueue.push({ frame, meta });
+}
+
+/**
+ * set the endpoint later frames are reported as coming from
+ * @param {string} uri - e.g. 'modbus://10.0.0.50:502'
+ * @param {string} id - the socket or serial port, e.g. 'plc-north'
+ */
+export function setSource(uri, id) {
+    sourceUri = uri;
+    connectionId = id;
+}
+
+/**
+ * the frame-meta record for a frame received now
+ */
+export function frameMeta() {
+    return { sourceUri, connectionId, receivedAt: wallClock() };
+}
+
+/**
+ * the current time as a wasi wall-clock datetime - jco maps u64 to bigint
+ */
+function wallClock() {
+    const ms = Date.now();
+    return {
+        seconds: BigInt(Math.floor(ms / 1000)),
+        nanoseconds: (ms % 1000) * 1_000_000,
+    };
 }
 
 export function setChaosMode(enabled) {
     chaosMode = enabled;
 }
 
+export function setFraming(mode) {
+    framing = mode;
+}
+
+/**
+ * wit interface implementation: source-framing
+ * jco maps wit enums to their kebab-case string names
+ */
+export function sourceFraming() {
+    return framing;
+}
+
 /**
  * build a valid modbus read holding registers response
  */
@@ -67,8 +118,40 @@ export function buildReadResponse(unitId, transactionId, registers) {
 }
 
 /**
- * jco expects this to return Uint8Array directly, not a Result wrapper
- * errors should be thrown as exceptions
+ * build a read holding registers request (mbap header + pdu).
+ * pass it to the guest's trackRequest export before the matching
+ * response so published registers carry their real addresses.
+ */
+export function buildReadRequest(unitId, transactionId, startAddress, quantity) {
+    return new Uint8Array([
+        (transactionId >> 8) & 0xFF, transactionId & 0xFF,
+        0x00, 0x00,
+        0x00, 0x06,
+        unitId,
+        0x03,
+        (startAddress >> 8) & 0xFF, startAddress & 0xFF,
+        (quantity >> 8) & 0xFF, quantity & 0xFF,
+    ]);
+}
+
+/**
+ * build a read device identification request (mbap header + pdu).
+ * the guest only publishes an identity whose request it tracked.
+ * @param {number} readCode - 1 basic, 2 regular, 3 extended, 4 one object
+ */
+export function buildDeviceIdRequest(unitId, transactionId, readCode = 1, objectId = 0) {
+    return new Uint8Array([
+        (transactionId >> 8) & 0xFF, transactionId & 0xFF,
+        0x00, 0x00,
+        0x00, 0x05,
+        unitId,
+        0x2B, 0x0E, readCode, objectId,
+    ]);
+}
+
+/**
+ * jco expects this to return the received-frame record directly, not a
+ * Result wrapper - errors should be thrown as exceptions
  */
 export function receiveFrame() {
     if (chaosMode && Math.random() < 0.3) {
@@ -76,14 +159,15 @@ export function receiveFrame() {
         const randomAttack = attackNames[Math.floor(Math.random() * attackNames.length)];
         const malformedFrame = CHAOS_ATTACKS[randomAttack]();
         console.log(`[MODBUS-SOURCE] Injecting chaos: ${randomAttack}`);
-        return malformedFrame;
+        return { data: malformedFrame, meta: frameMeta() };
     }
 
     if (frameQueue.length > 0) {
-        const frame = frameQueue.shift();
-        return frame instanceof Uint8Array ? frame : new Uint8Array(frame);
+        const { frame, meta } = frameQueue.shift();
+        const data = frame instanceof Uint8Array ? frame : new Uint8Array(frame);
+        return { data, meta };
     }
 
     // generate sample response
-    return buildReadResponse(1, 1, [1000, 2000, 3000, 4000, 5000]);
+    return { data: buildReadResponse(1, 1, [1000, 2000, 3000, 4000, 5000]), meta: frameMeta() };
 }
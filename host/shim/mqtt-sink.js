@@ -6,11 +6,13 @@
 // storage for published messages
 let publishedMessages = [];
 let publishCallback = null;
+// last will registered by the guest, sent by the broker if the session drops
+let will = null;
 
 /**
  * register a callback to be called on each publish
  * useful for real-time dashboard updates
- * @param {function} callback - receives (topic, payload, qos)
+ * @param {function} callback - receives (topic, payload, options)
  */
 export function onPublish(callback) {
     publishCallback = callback;
@@ -18,7 +20,7 @@ export function onPublish(callback) {
 
 /**
  * get all published messages
- * @returns {Array} array of {topic, payload, qos, timestamp} objects
+ * @returns {Array} array of {topic, payload, timestamp} objects, plus the publish options
  */
 export function getPublishedMessages() {
     return [...publishedMessages];
@@ -31,29 +33,69 @@ export function clearMessages() {
     publishedMessages = [];
 }
 
+/**
+ * get the registered last will
+ * @returns {{topic, payload, qos, ...} | null}
+ */
+export function getWill() {
+    return will;
+}
+
 /**
  * wit interface implementation: publish
  * stores the message and calls any registered callback
  * @param {string} topic - mqtt topic
  * @param {string} payload - json payload string
- * @param {number} qos - quality of service (0, 1, or 2)
+ * @param {{qos: number, retain: boolean, messageExpirySecs?: number,
+ *          contentType?: string, correlationData?: Uint8Array,
+ *          userProperties: Array<[string, string]>}} options
+ */
+export function publish(topic, payload, options) {
+    return store(topic, payload, options);
+}
+
+/**
+ * wit interface implementation: publish-binary
+ * @param {string} topic - mqtt topic
+ * @param {Uint8Array} payload - binary payload, e.g. sparkplug b protobuf
+ * @param {object} options - as for publish
  */
-export function publish(topic, payload, qos) {
+export function publishBinary(topic, payload, options) {
+    return store(topic, payload, options);
+}
+
+/**
+ * wit interface implementation: set-will
+ * a real client applies the will on its next connect
+ * @param {string} topic - mqtt topic
+ * @param {Uint8Array} payload - binary payload
+ * @param {object} options - as for publish
+ */
+export function setWill(topic, payload, options) {
+    will = { topic, payload, ...options };
+    console.log(`[MQTT-SINK] Will set on ${topic} (${payload.length} bytes)`);
+    return { tag: 'ok', val: undefined };
+}
+
+function store(topic, payload, options) {
     const message = {
         topic,
         payload,
-        qos,
+        ...options,
         timestamp: new Date().toISOString(),
     };
 
     publishedMessages.push(message);
 
     // log for demo visibility
-    console.log(`[MQTT-SINK] Published to ${topic}: ${payload.substring(0, 50)}...`);
+    const preview = typeof payload === 'string'
+        ? payload.substring(0, 50)
+        : `<${payload.length} bytes>`;
+    console.log(`[MQTT-SINK] Published to ${topic}: ${preview}...`);
 
     // call registered callback if any
     if (publishCallback) {
-        publishCallback(topic, payload, qos);
+        publishCallback(topic, payload, options);
     }
 
     // return success
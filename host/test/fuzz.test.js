@@ -9,6 +9,7 @@ import crypto from 'crypto';
 // import the actual jco-generated wasm component
 let wasmModule;
 let modbusSource;
+let mqttSink;
 
 // attack vectors - each designed to exploit a specific vulnerability
 const CHAOS_ATTACKS = {
@@ -77,6 +78,7 @@ describe('WASM Parser - Direct Malformed Frame Injection', () => {
     beforeAll(async () => {
         // import the modbus source with queueFrame capability
         modbusSource = await import('../shim/modbus-source.js');
+        mqttSink = await import('../shim/mqtt-sink.js');
         // import the actual wasm module
         wasmModule = await import('../protocol-gateway-guest.js');
     });
@@ -169,6 +171,60 @@ describe('WASM Parser - Direct Malformed Frame Injection', () => {
     });
 });
 
+describe('Host Interfaces - received-frame meta and publish options', () => {
+    beforeEach(() => {
+        mqttSink.clearMessages();
+        modbusSource.setSource('modbus://plc:502', 'plc-1');
+    });
+
+    it('receive-frame meta names the payload source', async () => {
+        modbusSource.setSource('modbus://10.0.0.50:502', 'plc-north');
+        modbusSource.queueFrame(buildValidResponse(1, 2, [1000, 2000, 3000]));
+        wasmModule.run();
+
+        const messages = mqttSink.getPublishedMessages();
+        expect(messages.length).toBeGreaterThan(0);
+        const payload = JSON.parse(messages[0].payload);
+        expect(payload.source).toBe('modbus://10.0.0.50:502');
+        expect(payload.received_at).toMatch(/^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}\.\d{3}Z$/);
+    });
+
+    it('publish passes publish-options, not a bare qos', async () => {
+        modbusSource.queueFrame(buildValidResponse(1, 3, [1000]));
+        wasmModule.run();
+
+        const [message] = mqttSink.getPublishedMessages();
+        expect(typeof message.qos).toBe('number');
+        expect(typeof message.retain).toBe('boolean');
+        expect(message.contentType).toBe('application/json');
+        expect(message.userProperties).toContainEqual(['schema_version', '1']);
+    });
+
+    it('oversized frame meta is dropped as a receive failure', async () => {
+        const processedBefore = Number(wasmModule.metrics.getStats().framesProcessed);
+
+        modbusSource.setSource('modbus://plc:502', 'c'.repeat(256));
+        modbusSource.queueFrame(buildValidResponse(1, 4, [1000]));
+        wasmModule.run();
+
+        const stats = wasmModule.metrics.getStats();
+        expect(Number(stats.framesProcessed)).toBe(processedBefore);
+        expect(stats.lastError?.tag).toBe('receive-failure');
+        expect(mqttSink.getPublishedMessages()).toHaveLength(0);
+    });
+
+    it('transact returns a received-frame record', async () => {
+        const modbusClient = await import('../shim/modbus-client.js');
+        const request = modbusSource.buildReadRequest(1, 5, 0, 2);
+
+        const reply = modbusClient.transact(request, 1000);
+        expect(reply.data).toBeInstanceOf(Uint8Array);
+        expect(reply.data[7]).toBe(0x03);
+        expect(reply.meta.sourceUri).toBe('modbus://plc:502');
+        expect(typeof reply.meta.receivedAt.seconds).toBe('bigint');
+    });
+});
+
 describe('Attack Vector Validation - Chaos Library', () => {
     it('bufferOverflow: length exceeds actual payload', () => {
         const frame = CHAOS_ATTACKS.bufferOverflow();